@@ -0,0 +1,25 @@
+//! Shared body of the fuzz targets, one per day in `fuzz_targets`.
+//!
+//! Run a day with `cargo +nightly fuzz run day5`, seeding it with the examples by passing
+//! `fuzz/corpus/day5 fixtures/day5` as corpus directories. A crash is a panic, an overflow
+//! (targets are built with debug assertions) or a run past libFuzzer's timeout.
+
+use advent_of_code_2023::registry;
+
+/// Parses `data` as the input of `day` and answers both parts when it parses.
+/// The solver is called directly, so a panic is not turned into an error.
+pub fn solve(day: u8, data: &[u8]) {
+    let day = registry::get(day).expect("the target's day is registered");
+
+    // inputs are read as UTF-8, anything else never reaches a parser
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let Ok(parsed) = day.solver.parse(input) else {
+        return;
+    };
+
+    let _ = day.solver.part1(parsed.as_ref());
+    let _ = day.solver.part2(parsed.as_ref());
+}
@@ -0,0 +1,117 @@
+use std::{collections::BTreeMap, fmt, fs, io, path::Path, str::FromStr};
+
+use serde::Deserialize;
+
+/// Accepted answers, keyed by day, input name and part.
+///
+/// ```toml
+/// [day1.input]
+/// part1 = 54634
+/// part2 = 53855
+/// ```
+///
+/// The input name is the file name of the input without `.txt`, so `[day1.input]` holds the
+/// answers for `day1/input.txt`.
+#[derive(Debug, Default, Deserialize)]
+pub struct Answers(BTreeMap<String, BTreeMap<String, Parts>>);
+
+#[derive(Debug, Default, Deserialize)]
+struct Parts {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+}
+
+// answers are usually numbers, strings cover the rest
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Answer {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl FromStr for Answers {
+    type Err = toml::de::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        toml::from_str(input)
+    }
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+
+        text.parse().map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {e}", path.display()),
+            )
+        })
+    }
+
+    /// Names of the inputs with stored answers for `day`, in order.
+    pub fn inputs(&self, day: u8) -> impl Iterator<Item = &str> {
+        self.0
+            .get(&format!("day{day}"))
+            .into_iter()
+            .flat_map(|inputs| inputs.keys().map(String::as_str))
+    }
+
+    pub fn get(&self, day: u8, input: &str, part: u8) -> Option<String> {
+        let parts = self.0.get(&format!("day{day}"))?.get(input)?;
+
+        match part {
+            1 => parts.part1.as_ref(),
+            2 => parts.part2.as_ref(),
+            _ => None,
+        }
+        .map(Answer::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_get_works() {
+        let answers = Answers::from_str(
+            r#"
+[day1.input]
+part1 = 54634
+part2 = "abc"
+
+[day1.example]
+part1 = 142
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            answers.inputs(1).collect::<Vec<_>>(),
+            vec!["example", "input"]
+        );
+        assert_eq!(answers.get(1, "input", 1), Some("54634".to_string()));
+        assert_eq!(answers.get(1, "input", 2), Some("abc".to_string()));
+        assert_eq!(answers.get(1, "example", 2), None);
+        assert_eq!(answers.get(2, "input", 1), None);
+        assert_eq!(answers.inputs(2).count(), 0);
+    }
+
+    #[test]
+    fn stored_answers_load() {
+        let answers = Answers::from_str(include_str!("../answers.toml")).unwrap();
+
+        assert_eq!(answers.get(1, "input", 1), Some("54634".to_string()));
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{
+    error::SolveError,
+    output::{format_duration, nanos},
+    registry::Day,
+};
+
+/// Timings of every phase of a day over repeated runs.
+#[derive(Debug, Serialize)]
+pub struct DayTimings {
+    pub day: u8,
+    pub runs: usize,
+    pub parse: Stats,
+    /// `None` when the part was not benchmarked
+    pub part1: Option<Stats>,
+    pub part2: Option<Stats>,
+}
+
+impl DayTimings {
+    /// One row per benchmarked phase, lined up under [`table_header`].
+    pub fn table_rows(&self) -> Vec<String> {
+        let phases = [
+            ("parse", Some(&self.parse)),
+            ("part1", self.part1.as_ref()),
+            ("part2", self.part2.as_ref()),
+        ];
+
+        phases
+            .into_iter()
+            .filter_map(|(phase, stats)| {
+                let Stats { min, median, max } = stats?;
+
+                Some(format!(
+                    "{:>4} {phase:<6} {:>12} {:>12} {:>12}",
+                    self.day,
+                    format_duration(*min),
+                    format_duration(*median),
+                    format_duration(*max)
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Header of the table printed by `aoc bench`.
+pub fn table_header() -> String {
+    format!(
+        "{:>4} {:<6} {:>12} {:>12} {:>12}",
+        "day", "phase", "min", "median", "max"
+    )
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Stats {
+    #[serde(rename = "min_ns", serialize_with = "nanos")]
+    pub min: Duration,
+    #[serde(rename = "median_ns", serialize_with = "nanos")]
+    pub median: Duration,
+    #[serde(rename = "max_ns", serialize_with = "nanos")]
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarizes `samples`, `None` when there are none.
+    pub fn new(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort();
+
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (*samples.get(mid.checked_sub(1)?)? + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Some(Stats {
+            min: *samples.first()?,
+            median,
+            max: *samples.last()?,
+        })
+    }
+}
+
+/// Parses `input` and answers the parts `runs` times, timing each phase on its own.
+/// Only `part` is answered when it is given. Fails when `runs` is 0, as there is nothing to time.
+pub fn bench(
+    day: &Day,
+    input: &str,
+    runs: usize,
+    part: Option<u8>,
+) -> Result<DayTimings, SolveError> {
+    let mut parse = Vec::with_capacity(runs);
+    let mut parts = [Vec::with_capacity(runs), Vec::with_capacity(runs)];
+
+    for _ in 0..runs {
+        let start = Instant::now();
+        let parsed = day.parse(black_box(input))?;
+        parse.push(start.elapsed());
+
+        for (p, samples) in (1..=2).zip(parts.iter_mut()) {
+            if part.is_some_and(|part| part != p) {
+                continue;
+            }
+
+            let start = Instant::now();
+            black_box(day.answer(parsed.as_ref(), p)?);
+            samples.push(start.elapsed());
+        }
+    }
+
+    let parse = Stats::new(parse)
+        .ok_or_else(|| SolveError::NoAnswer("there are no runs to time".into()))?;
+    let [part1, part2] = parts.map(Stats::new);
+
+    Ok(DayTimings {
+        day: day.number,
+        runs,
+        parse,
+        part1,
+        part2,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn stats_new_works() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(
+            Stats::new(vec![ms(3), ms(1), ms(2)]),
+            Some(Stats {
+                min: ms(1),
+                median: ms(2),
+                max: ms(3)
+            })
+        );
+        assert_eq!(
+            Stats::new(vec![ms(4), ms(1)]).unwrap().median,
+            Duration::from_micros(2500)
+        );
+        assert_eq!(Stats::new(vec![]), None);
+    }
+
+    #[test]
+    fn bench_times_requested_parts() {
+        let day = registry::get(15).unwrap();
+        let timings = bench(day, "rn=1,cm-,qp=3", 3, Some(2)).unwrap();
+
+        assert_eq!(timings.runs, 3);
+        assert!(timings.part1.is_none());
+        assert!(timings.part2.is_some());
+        assert!(bench(day, "rn=1", 0, None).is_err());
+    }
+
+    #[test]
+    fn table_rows_line_up_with_the_header() {
+        let ms = Duration::from_millis;
+        let stats = || Stats {
+            min: ms(1),
+            median: ms(2),
+            max: ms(30),
+        };
+        let timings = DayTimings {
+            day: 7,
+            runs: 3,
+            parse: stats(),
+            part1: None,
+            part2: Some(stats()),
+        };
+
+        assert_eq!(
+            table_header(),
+            " day phase           min       median          max"
+        );
+        assert_eq!(
+            timings.table_rows(),
+            [
+                "   7 parse        1.00ms       2.00ms      30.00ms",
+                "   7 part2        1.00ms       2.00ms      30.00ms"
+            ]
+        );
+    }
+}
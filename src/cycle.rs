@@ -0,0 +1,118 @@
+/// Where the sequence `start, next(start), next(next(start)), ...` starts repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Number of states before the first one that comes back
+    pub prefix: usize,
+    /// Number of states in one round of the cycle, at least 1
+    pub len: usize,
+}
+
+impl Cycle {
+    /// Index of a state equal to the state at `i`, below `prefix + len`.
+    pub fn index(&self, i: usize) -> usize {
+        if i < self.prefix {
+            i
+        } else {
+            self.prefix + (i - self.prefix) % self.len
+        }
+    }
+}
+
+/// Finds the cycle of the states reached from `start` with Brent's algorithm. States are
+/// compared in full, so two states are only taken to be the same when they are equal.
+///
+/// `next` has to be deterministic and only reach finitely many states, otherwise this never
+/// returns.
+pub fn find<T: Clone + PartialEq>(start: T, mut next: impl FnMut(&T) -> T) -> Cycle {
+    // the hare runs ahead, the tortoise jumps to it every power of two steps
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = start.clone();
+    let mut hare = next(&start);
+
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+
+        hare = next(&hare);
+        len += 1;
+    }
+
+    // with the hare a whole cycle ahead both meet where the cycle starts
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..len {
+        hare = next(&hare);
+    }
+
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        prefix += 1;
+    }
+
+    Cycle { prefix, len }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    // remembers every state until one comes back
+    fn find_slowly(start: u64, next: impl Fn(&u64) -> u64) -> Cycle {
+        let mut seen = HashMap::new();
+        let mut state = start;
+
+        for i in 0.. {
+            if let Some(first) = seen.insert(state, i) {
+                return Cycle {
+                    prefix: first,
+                    len: i - first,
+                };
+            }
+            state = next(&state);
+        }
+
+        unreachable!()
+    }
+
+    #[test]
+    fn find_works() {
+        for modulus in 1..200 {
+            for start in [0, 1, modulus / 2, modulus - 1] {
+                let next = |x: &u64| (x * x + 1) % modulus;
+
+                assert_eq!(
+                    find(start, next),
+                    find_slowly(start, next),
+                    "x * x + 1 modulo {modulus} from {start}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn find_handles_fixed_points() {
+        assert_eq!(find(7, |x| *x), Cycle { prefix: 0, len: 1 });
+        assert_eq!(
+            find(3u32, |x| x.saturating_sub(1)),
+            Cycle { prefix: 3, len: 1 }
+        );
+    }
+
+    #[test]
+    fn cycle_index_works() {
+        let cycle = Cycle { prefix: 3, len: 4 };
+
+        assert_eq!(cycle.index(2), 2);
+        assert_eq!(cycle.index(3), 3);
+        assert_eq!(cycle.index(7), 3);
+        assert_eq!(cycle.index(1_000_000_002), 6);
+    }
+}
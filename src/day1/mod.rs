@@ -1,73 +1,100 @@
+use std::fmt::Display;
+
 use regex::Regex;
+use tracing::trace;
+
+use crate::{
+    error::{ParseError, SolveError},
+    solution::Solution,
+};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Parsed = String;
 
-pub fn solve() {
-    let input = include_str!("./input.txt");
-    let res = decrypt_lines(input);
-    dbg!(res);
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Parsed) -> Result<impl Display, SolveError> {
+        decrypt_lines(input)
+    }
 
-    let res = decrypt_lines2(input);
-    dbg!(res);
+    fn part2(input: &Self::Parsed) -> Result<impl Display, SolveError> {
+        decrypt_lines2(input)
+    }
 }
 
-fn decrypt_lines(input: &str) -> u32 {
-    let lines = input.split("\n");
-    lines.map(decrypt_line).sum()
+pub fn decrypt_lines(input: &str) -> Result<u64, SolveError> {
+    sum_lines(input, decrypt_line)
 }
 
-fn decrypt_lines2(input: &str) -> u32 {
-    let lines = input.split("\n");
+pub fn decrypt_lines2(input: &str) -> Result<u64, SolveError> {
+    sum_lines(input, decrypt_line2)
+}
 
-    lines.map(decrypt_line2).sum()
+// every line has to give a value, a line is at most 99 so the sum can't overflow
+fn sum_lines(input: &str, decrypt: fn(&str) -> Option<u32>) -> Result<u64, SolveError> {
+    input
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            decrypt(line)
+                .map(u64::from)
+                .ok_or_else(|| SolveError::NoAnswer(format!("line {} has no digits", i + 1)))
+        })
+        .sum()
 }
 
-fn decrypt_line(line: &str) -> u32 {
+/// `None` when the line has no digits.
+pub fn decrypt_line(line: &str) -> Option<u32> {
     let digits = line
         .chars()
-        .filter(|c| c.is_numeric())
-        .map(|d| d.to_digit(10).unwrap())
+        .filter_map(|c| c.to_digit(10))
         .collect::<Vec<_>>();
 
-    let first = digits.first().unwrap();
-    let last = digits.last().unwrap();
+    let first = digits.first()?;
+    let last = digits.last()?;
 
-    first * 10 + last
+    Some(first * 10 + last)
 }
 
-fn decrypt_line2(line: &str) -> u32 {
-    dbg!(line);
-    let digits = get_digits(line);
-    dbg!(&digits);
+/// `None` when the line has no digits, spelled out or not.
+pub fn decrypt_line2(line: &str) -> Option<u32> {
+    let digits = get_digits(line)?;
+    trace!(line, ?digits, "decrypted line");
     let first = digits.0;
     let last = digits.1;
 
-    first * 10 + last
+    Some(first * 10 + last)
 }
 
 fn rev(line: &str) -> String {
     line.chars().rev().collect()
 }
 
-fn get_digits(line: &str) -> (u32, u32) {
-    let regex = Regex::new(r"(\d|zero|one|two|three|four|five|six|seven|eight|nine)").unwrap();
+fn get_digits(line: &str) -> Option<(u32, u32)> {
+    let regex = Regex::new(r"([0-9]|zero|one|two|three|four|five|six|seven|eight|nine)").unwrap();
     let reverse_regex =
-        Regex::new(&r"(\d|enin|thgie|neves|xis|evif|ruof|eerht|owt|eno|orez)").unwrap();
+        Regex::new(r"([0-9]|enin|thgie|neves|xis|evif|ruof|eerht|owt|eno|orez)").unwrap();
 
     let rev_line: String = rev(line);
 
-    let first = word_to_digit(regex.find(line).unwrap().as_str());
-    let last = word_to_digit(&rev(&reverse_regex.find(&rev_line).unwrap().as_str()));
+    let first = word_to_digit(regex.find(line)?.as_str())?;
+    let last = word_to_digit(&rev(reverse_regex.find(&rev_line)?.as_str()))?;
 
-    (first, last)
+    Some((first, last))
 }
 
-fn word_to_digit(word: &str) -> u32 {
+fn word_to_digit(word: &str) -> Option<u32> {
     if word.len() == 1 {
-        if let Some(value) = word.chars().nth(0).and_then(|c| c.to_digit(10)) {
-            return value;
+        if let Some(value) = word.chars().next().and_then(|c| c.to_digit(10)) {
+            return Some(value);
         }
     }
 
-    match word.to_lowercase().as_str() {
+    let value = match word.to_lowercase().as_str() {
         "zero" => 0,
         "one" => 1,
         "two" => 2,
@@ -78,8 +105,10 @@ fn word_to_digit(word: &str) -> u32 {
         "seven" => 7,
         "eight" => 8,
         "nine" => 9,
-        _ => panic!("non valid digit"),
-    }
+        _ => return None,
+    };
+
+    Some(value)
 }
 
 #[cfg(test)]
@@ -88,7 +117,8 @@ mod tests {
 
     #[test]
     fn decrypt_line_returns_number() {
-        assert_eq!(decrypt_line("1ab3c2"), 12);
+        assert_eq!(decrypt_line("1ab3c2"), Some(12));
+        assert_eq!(decrypt_line("abc"), None);
     }
 
     #[test]
@@ -98,18 +128,18 @@ pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet";
 
-        assert_eq!(decrypt_lines(input), 142);
+        assert_eq!(decrypt_lines(input).unwrap(), 142);
     }
 
     #[test]
     fn get_digits_reutrns_word_digits() {
         let input = "two1blanine5";
         let result = get_digits(input);
-        assert_eq!(result, (2, 5));
+        assert_eq!(result, Some((2, 5)));
 
         let input = "three98oneightzn";
         let result = get_digits(input);
-        assert_eq!(result, (3, 8));
+        assert_eq!(result, Some((3, 8)));
     }
 
     #[test]
@@ -122,6 +152,13 @@ xtwone3four
 zoneight234
 7pqrstsixteen";
 
-        assert_eq!(decrypt_lines2(input), 281);
+        assert_eq!(decrypt_lines2(input).unwrap(), 281);
+    }
+
+    #[test]
+    fn decrypt_lines_reports_lines_without_digits() {
+        let error = decrypt_lines("1abc2\nabc\n3").unwrap_err();
+
+        assert_eq!(error.to_string(), "no answer: line 2 has no digits");
     }
 }
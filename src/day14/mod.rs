@@ -1,287 +1,151 @@
-use std::{
-    collections::{hash_map::DefaultHasher, HashMap},
-    fmt::Write,
-    hash::{Hash, Hasher},
-    str::FromStr,
-};
-
-const ROUND: char = 'O';
-const CUBE: char = '#';
-
-pub fn solve() {
-    let input = include_str!("./input.txt");
-    let mut board: Board = input.parse().unwrap();
-    board.tilt_north();
+use std::fmt::Display;
 
-    dbg!(board.get_load());
+use crate::{
+    cycle,
+    error::{ParseError, SolveError},
+    grid::{Cell, Dir, Grid},
+    solution::Solution,
+};
 
-    let board: Board = input.parse().unwrap();
+pub const ROUND: char = 'O';
+pub const CUBE: char = '#';
 
-    let times = 1_000_000_000;
+pub struct Day14;
 
-    dbg!(tilt_cycles(board, times));
-}
+impl Solution for Day14 {
+    type Parsed = Board;
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-struct SparseCell {
-    symbol: char,
-    row: usize,
-    col: usize,
-}
-
-impl ToString for SparseCell {
-    fn to_string(&self) -> String {
-        self.symbol.to_string()
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        input.parse()
     }
-}
-
-#[derive(Clone, PartialEq, Hash, Eq)]
-struct Board {
-    width: usize,
-    height: usize,
-
-    rows: Vec<Vec<SparseCell>>,
-    columns: Vec<Vec<SparseCell>>,
-}
 
-impl<'a> std::fmt::Debug for Board {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let cells = self.rows.iter().flatten().collect::<Vec<_>>();
-        f.write_char('\n')?;
-        for row_i in 0..self.height {
-            for col_i in 0..self.width {
-                if let Some(cell) = cells.iter().find(|c| c.row == row_i && c.col == col_i) {
-                    f.write_str(&cell.to_string())?;
-                } else {
-                    f.write_str(".")?;
-                }
-            }
-            f.write_char('\n')?;
-        }
+    fn part1(board: &Self::Parsed) -> Result<impl Display, SolveError> {
+        Ok(board.tilt_north().get_load())
+    }
 
-        Ok(())
+    fn part2(board: &Self::Parsed) -> Result<impl Display, SolveError> {
+        Ok(tilt_cycles(board.clone(), 1_000_000_000))
     }
 }
 
-impl<'a> FromStr for Board {
-    type Err = ();
-
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let lines = input.split('\n');
-        let height = lines.clone().count();
-        let width = lines.clone().next().unwrap().len();
-
-        let mut rows = Vec::new();
-        let mut columns = Vec::new();
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Rock {
+    Empty,
+    Round,
+    Cube,
+}
 
-        for _ in 0..height {
-            rows.push(Vec::new());
-        }
+impl Cell for Rock {
+    const EXPECTED: &'static str = "\".\", \"O\" or \"#\"";
 
-        for _ in 0..width {
-            columns.push(Vec::new());
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Rock::Empty),
+            ROUND => Some(Rock::Round),
+            CUBE => Some(Rock::Cube),
+            _ => None,
         }
-
-        for (row_i, line) in lines.enumerate() {
-            for (col_i, char) in line.trim().chars().enumerate() {
-                if char == ROUND || char == CUBE {
-                    let cell = SparseCell {
-                        symbol: char,
-                        row: row_i,
-                        col: col_i,
-                    };
-
-                    rows[row_i].push(cell.clone());
-                    columns[col_i].push(cell.clone());
-                }
-            }
-        }
-
-        Ok(Board {
-            rows,
-            columns,
-            width,
-            height,
-        })
     }
-}
 
-impl Board {
-    fn new(width: usize, height: usize) -> Self {
-        let rows = vec![Vec::new(); height];
-        let columns = vec![Vec::new(); width];
-
-        Board {
-            width,
-            height,
-            rows,
-            columns,
+    fn to_char(&self) -> char {
+        match self {
+            Rock::Empty => '.',
+            Rock::Round => ROUND,
+            Rock::Cube => CUBE,
         }
     }
+}
 
-    fn add(&mut self, cell: SparseCell) {
-        self.rows[cell.row].push(cell.clone());
-        self.columns[cell.col].push(cell);
-    }
-
-    fn tilt_north(&mut self) -> Self {
-        let mut board = Board::new(self.width, self.height);
-
-        for col in self.columns.iter() {
-            let mut cur_fall = 0;
-            for cell in col.iter() {
-                let cur_loc = match cell.symbol {
-                    ROUND => cur_fall,
-                    CUBE => cell.row,
-                    _ => panic!("bad input"),
-                };
-
-                let new_cell = SparseCell {
-                    symbol: cell.symbol,
-                    row: cur_loc,
-                    col: cell.col,
-                };
+pub type Board = Grid<Rock>;
 
-                cur_fall = cur_loc + 1;
+impl Board {
+    /// Rolls every round rock towards `dir` until it hits a cube, another rock or the edge.
+    pub fn tilt(&self, dir: Dir) -> Self {
+        let mut board = self.clone();
+        let back = dir.opposite();
+
+        for start in self.edge(dir) {
+            // where the next round rock of this lane comes to rest
+            let mut free = Some(start);
+            let mut pos = Some(start);
+
+            while let Some(cur) = pos {
+                match board[cur] {
+                    Rock::Cube => free = board.step(cur, back),
+                    Rock::Round => {
+                        let rest = free.expect("a round rock always has room behind it");
+                        board[cur] = Rock::Empty;
+                        board[rest] = Rock::Round;
+                        free = board.step(rest, back);
+                    }
+                    Rock::Empty => {}
+                }
 
-                board.add(new_cell);
+                pos = board.step(cur, back);
             }
         }
 
         board
     }
 
-    fn tilt_south(&mut self) -> Self {
-        let mut board = Board::new(self.width, self.height);
-
-        for col in self.columns.iter() {
-            let mut cur_fall = self.height - 1;
-            for cell in col.iter().rev() {
-                let cur_loc = match cell.symbol {
-                    ROUND => cur_fall,
-                    CUBE => cell.row,
-                    _ => panic!("bad input"),
-                };
-
-                let new_cell = SparseCell {
-                    symbol: cell.symbol,
-                    row: cur_loc,
-                    col: cell.col,
-                };
-
-                if cur_loc != 0 {
-                    cur_fall = cur_loc - 1;
-                }
-                board.add(new_cell);
-            }
-        }
-
-        board
+    pub fn tilt_north(&self) -> Self {
+        self.tilt(Dir::Up)
     }
 
-    fn tilt_west(&mut self) -> Self {
-        let mut board = Board::new(self.width, self.height);
-
-        for row in self.rows.iter() {
-            let mut cur_fall = 0;
-            for cell in row.iter() {
-                let cur_loc = match cell.symbol {
-                    ROUND => cur_fall,
-                    CUBE => cell.col,
-                    _ => panic!("bad input"),
-                };
-
-                let new_cell = SparseCell {
-                    symbol: cell.symbol,
-                    row: cell.row,
-                    col: cur_loc,
-                };
-
-                cur_fall = cur_loc + 1;
-
-                board.add(new_cell);
-            }
-        }
-
-        board
+    pub fn tilt_south(&self) -> Self {
+        self.tilt(Dir::Down)
     }
 
-    fn tilt_east(&mut self) -> Self {
-        let mut board = Board::new(self.width, self.height);
-
-        for row in self.rows.iter() {
-            let mut cur_fall = self.width - 1;
-            for cell in row.iter().rev() {
-                let cur_loc = match cell.symbol {
-                    ROUND => cur_fall,
-                    CUBE => cell.col,
-                    _ => panic!("bad input"),
-                };
-
-                let new_cell = SparseCell {
-                    symbol: cell.symbol,
-                    row: cell.row,
-                    col: cur_loc,
-                };
-
-                if cur_loc != 0 {
-                    cur_fall = cur_loc - 1;
-                }
-                board.add(new_cell);
-            }
-        }
-
-        board
+    pub fn tilt_west(&self) -> Self {
+        self.tilt(Dir::Left)
     }
 
-    pub fn get_load(&self) -> u32 {
-        let cells = self.rows.iter().flatten().collect::<Vec<_>>();
+    pub fn tilt_east(&self) -> Self {
+        self.tilt(Dir::Right)
+    }
 
-        cells
-            .iter()
-            .filter(|cell| cell.symbol == ROUND)
-            .map(|cell| (self.height - cell.row) as u32)
+    pub fn get_load(&self) -> u64 {
+        self.iter()
+            .filter(|(_, rock)| **rock == Rock::Round)
+            .map(|(pos, _)| (self.height() - pos.y) as u64)
             .sum()
     }
 }
 
-fn tilt_cycles(mut board: Board, times: usize) -> u32 {
-    let mut boards = HashMap::new();
-    let mut loads: Vec<u32> = Vec::new();
-
-    // .... (cycle start ... cycle end)(...)(...)...(..times
-    // .....A................B
-    // matching board to {times} cycles = (times - A) % (B - A)
-    for i in 0..times / 4 {
-        board = board.tilt_north();
-        board = board.tilt_west();
-        board = board.tilt_south();
-        board = board.tilt_east();
-
-        let mut hasher = DefaultHasher::new();
-        board.hash(&mut hasher);
-        let hash = hasher.finish();
-
-        if let Some(cycle_i) = boards.get(&hash) {
-            let cycle_len = i - cycle_i;
-            let last_cycle_reminder = (times - cycle_i) % (cycle_len);
-            return loads
-                .get(cycle_i + last_cycle_reminder as usize - 1)
-                .unwrap()
-                .clone();
-        }
+/// One spin cycle, tilting north, west, south and then east.
+pub fn spin(board: &Board) -> Board {
+    board.tilt_north().tilt_west().tilt_south().tilt_east()
+}
 
-        boards.insert(hash, i);
-        loads.push(board.get_load());
-    }
+/// Load on the north beams after `times` spin cycles.
+pub fn tilt_cycles(board: Board, times: usize) -> u64 {
+    // the boards repeat after a while, the one after `times` cycles is the same as one of the
+    // first round
+    let cycle = cycle::find(board.clone(), spin);
 
-    board.get_load()
+    (0..cycle.index(times))
+        .fold(board, |board, _| spin(&board))
+        .get_load()
 }
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
 
+    #[test]
+    fn board_from_str_reports_bad_cell() {
+        let error = "O..#\n.#x.".parse::<Board>().unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = "O..#\n.#...".parse::<Board>().unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.expected, "end of line");
+    }
+
     #[test]
     fn board_from_str_woks() {
         let input = "O....#....
@@ -296,7 +160,7 @@ O.#..O.#.#
 #OO..#....";
         let board: Board = input.parse().unwrap();
 
-        dbg!(board);
+        assert_eq!(board.to_string(), input);
     }
 
     #[test]
@@ -311,7 +175,7 @@ O.#..O.#.#
 .......O..
 #....###..
 #OO..#....";
-        let mut board: Board = input.parse().unwrap();
+        let board: Board = input.parse().unwrap();
         dbg!(&board);
 
         let board = board.tilt_north();
@@ -336,4 +200,49 @@ O.#..O.#.#
 
         assert_eq!(tilt_cycles(board, 1_000_000_000), 64);
     }
+
+    #[test]
+    fn tilt_cycles_handles_boards_that_never_move() {
+        let board: Board = "#.\n..".parse().unwrap();
+        assert_eq!(tilt_cycles(board, 1_000_000_000), 0);
+
+        let board: Board = "O".parse().unwrap();
+        assert_eq!(tilt_cycles(board, 3), 1);
+    }
+
+    fn board() -> impl Strategy<Value = Board> {
+        let rock = prop_oneof![Just(Rock::Empty), Just(Rock::Round), Just(Rock::Cube)];
+
+        (1..12usize, 1..12usize).prop_flat_map(move |(width, height)| {
+            prop::collection::vec(prop::collection::vec(rock.clone(), width), height)
+                .prop_map(|rows| Board::from_rows(rows).unwrap())
+        })
+    }
+
+    fn round_rocks(board: &Board) -> usize {
+        board
+            .iter()
+            .filter(|(_, rock)| **rock == Rock::Round)
+            .count()
+    }
+
+    proptest! {
+        #[test]
+        fn tilt_conserves_rocks(board in board()) {
+            for dir in Dir::ALL {
+                let tilted = board.tilt(dir);
+
+                prop_assert_eq!(round_rocks(&tilted), round_rocks(&board));
+                for (pos, rock) in board.iter() {
+                    prop_assert_eq!(*rock == Rock::Cube, tilted[pos] == Rock::Cube);
+                }
+                prop_assert_eq!(tilted.tilt(dir), tilted);
+            }
+        }
+
+        #[test]
+        fn board_display_round_trips(board in board()) {
+            prop_assert_eq!(board.to_string().parse::<Board>().unwrap(), board);
+        }
+    }
 }
@@ -1,117 +1,171 @@
-use regex::Regex;
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
 
-pub fn solve() {
-    let input = include_str!("./input.txt");
+use tracing::trace;
 
-    dbg!(hash_all(input));
+use crate::{
+    error::{parse_number, ParseError, SolveError},
+    solution::Solution,
+};
 
-    let commands = parse(input);
-    let mut machine = Machine::new();
-    machine.run(commands);
+pub struct Day15;
 
-    dbg!(machine.get_focusing_power());
+impl Solution for Day15 {
+    // part 1 hashes the raw steps, only part 2 reads them as commands
+    type Parsed = (String, Vec<Command>);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok((input.to_string(), parse(input)?))
+    }
+
+    fn part1((input, _): &Self::Parsed) -> Result<impl Display, SolveError> {
+        Ok(hash_all(input))
+    }
+
+    fn part2((_, commands): &Self::Parsed) -> Result<impl Display, SolveError> {
+        let mut machine = Machine::new();
+        machine.run(commands.clone());
+
+        Ok(machine.get_focusing_power())
+    }
 }
 
-#[derive(PartialEq, Debug)]
-enum Op {
+#[derive(PartialEq, Debug, Clone)]
+pub enum Op {
     Eq(u32),
     Dash,
 }
 
-#[derive(PartialEq, Debug)]
-struct Command {
+#[derive(PartialEq, Debug, Clone)]
+pub struct Command {
     pub label: String,
     pub op: Op,
 }
 
-impl Command {
-    pub fn from_str(input: &str) -> Self {
-        let command_regex = Regex::new(r"([a-zA-Z]+)(-|=\d)").unwrap();
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.op {
+            Op::Eq(focal) => write!(f, "{}={focal}", self.label),
+            Op::Dash => write!(f, "{}-", self.label),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = ParseError;
 
-        let parts = command_regex.captures(input).unwrap();
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let op_start = input
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(input.len());
+        let (label, op) = input.split_at(op_start);
 
-        let label = parts.get(1).unwrap().as_str().to_string();
-        let op = parts.get(2).unwrap().as_str();
+        if label.is_empty() {
+            return Err(ParseError::new(input, input, "a label"));
+        }
 
-        let op = if op.starts_with("-") {
+        let op = if op == "-" {
             Op::Dash
+        } else if let Some(focal) = op.strip_prefix('=') {
+            let length = parse_number(input, focal)?;
+            if !(1..=9).contains(&length) {
+                return Err(ParseError::new(input, focal, "a focal length from 1 to 9"));
+            }
+
+            Op::Eq(length)
         } else {
-            Op::Eq(op[1..2].parse().unwrap())
+            return Err(ParseError::new(input, op, "\"-\" or \"=<focal>\""));
         };
 
-        Self { label, op }
+        Ok(Self {
+            label: label.to_string(),
+            op,
+        })
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
-struct Lens {
+pub struct Lens {
     pub label: String,
     pub focal: u32,
 }
 
 #[derive(Default, Clone, Debug)]
-struct LensBox {
+pub struct LensBox {
     pub lenses: Vec<Lens>,
 }
 
 #[derive(Debug)]
-struct Machine {
+pub struct Machine {
     pub boxes: Vec<LensBox>,
 }
 
+impl Default for Machine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Machine {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             boxes: vec![LensBox::default(); 256],
         }
     }
 
-    fn run(&mut self, commands: Vec<Command>) {
-        for command in commands {
-            let label = command.label.clone();
-
-            match command.op {
-                Op::Eq(focal) => {
-                    let box_i = hash(&label) as usize;
-                    let lens_box = self.boxes.get_mut(box_i).unwrap();
+    pub fn run(&mut self, commands: Vec<Command>) {
+        for command in &commands {
+            self.apply(command);
+        }
+    }
 
-                    if let Some(existing_lens) =
-                        lens_box.lenses.iter_mut().find(|l| l.label == label)
-                    {
-                        existing_lens.focal = focal;
-                    } else {
-                        lens_box.lenses.push(Lens { label, focal });
-                    }
-                }
-                Op::Dash => {
-                    let box_i = hash(&label) as usize;
-                    let lens_box = self.boxes.get_mut(box_i).unwrap();
-                    lens_box.lenses.retain(|l| l.label != label)
+    /// Runs a single step of the initialization sequence.
+    pub fn apply(&mut self, command: &Command) {
+        let label = &command.label;
+        let lens_box = &mut self.boxes[hash(label) as usize];
+
+        match command.op {
+            Op::Eq(focal) => {
+                if let Some(existing_lens) = lens_box.lenses.iter_mut().find(|l| &l.label == label)
+                {
+                    existing_lens.focal = focal;
+                } else {
+                    lens_box.lenses.push(Lens {
+                        label: label.clone(),
+                        focal,
+                    });
                 }
             }
+            Op::Dash => lens_box.lenses.retain(|l| &l.label != label),
         }
     }
 
-    fn get_focusing_power(&self) -> u32 {
+    pub fn get_focusing_power(&self) -> u64 {
         self.boxes
             .iter()
             .enumerate()
             .flat_map(|(i, b)| b.lenses.iter().enumerate().zip(std::iter::repeat(i)))
-            .map(|((lens_i, lens), box_i)| (box_i as u32 + 1) * (lens_i as u32 + 1) * lens.focal)
+            .map(|((lens_i, lens), box_i)| {
+                (box_i as u64 + 1) * (lens_i as u64 + 1) * u64::from(lens.focal)
+            })
             .sum()
     }
 }
 
-fn parse(input: &str) -> Vec<Command> {
-    input.split(",").map(Command::from_str).collect()
+pub fn parse(input: &str) -> Result<Vec<Command>, ParseError> {
+    input
+        .split(',')
+        .map(|step| Command::from_str(step).map_err(|e| e.within(input, step)))
+        .collect()
 }
 
-fn hash_all(input: &str) -> u32 {
+pub fn hash_all(input: &str) -> u32 {
     input.split(',').map(hash).sum()
 }
 
-fn hash(input: &str) -> u32 {
-    dbg!(input);
+pub fn hash(input: &str) -> u32 {
     let mut cur = 0;
 
     for char in input.chars() {
@@ -119,11 +173,15 @@ fn hash(input: &str) -> u32 {
         cur = (cur + code) * 17 % 256
     }
 
+    trace!(input, hash = cur, "hashed step");
+
     cur
 }
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -152,7 +210,7 @@ mod test {
     #[test]
     fn command_from_str_works() {
         assert_eq!(
-            Command::from_str("rn=1"),
+            Command::from_str("rn=1").unwrap(),
             Command {
                 label: "rn".into(),
                 op: Op::Eq(1)
@@ -160,7 +218,7 @@ mod test {
         );
 
         assert_eq!(
-            Command::from_str("cm-"),
+            Command::from_str("cm-").unwrap(),
             Command {
                 label: "cm".into(),
                 op: Op::Dash
@@ -168,9 +226,23 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_reports_bad_step() {
+        let error = parse("rn=1,cm-,qp+3").unwrap_err();
+
+        assert_eq!(error.column, 12);
+        assert_eq!(error.found(), Some("+3"));
+
+        let error = parse("rn=1,cm=10").unwrap_err();
+        assert_eq!(
+            (error.column, error.expected.as_str()),
+            (9, "a focal length from 1 to 9")
+        );
+    }
+
     #[test]
     fn machine_run_works() {
-        let commands = parse("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7");
+        let commands = parse("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7").unwrap();
         let mut machine = Machine::new();
         machine.run(commands);
 
@@ -208,4 +280,18 @@ mod test {
 
         assert_eq!(machine.get_focusing_power(), 145);
     }
+
+    proptest! {
+        #[test]
+        fn hash_is_below_256(input in any::<String>()) {
+            prop_assert!(hash(&input) < 256);
+        }
+
+        #[test]
+        fn hash_all_sums_steps(steps in prop::collection::vec("[a-z]{1,6}(=[1-9]|-)", 1..20)) {
+            let expected = steps.iter().map(|step| hash(step)).sum::<u32>();
+
+            prop_assert_eq!(hash_all(&steps.join(",")), expected);
+        }
+    }
 }
@@ -1,25 +1,41 @@
-use std::collections::HashSet;
-
-pub fn solve() {
-    let input = include_str!("./input.txt");
-    let board = Board::from_str(input);
-
-    let res = simulate(
-        &board,
-        Ray {
-            x: 0,
-            y: 0,
-            dir: Dir::Right,
-        },
-    );
-    dbg!(res);
+use std::{collections::HashSet, fmt::Display};
+
+use tracing::trace;
+
+use crate::{
+    error::{ParseError, SolveError},
+    grid::{Cell, Grid, Pos},
+    solution::Solution,
+};
+
+pub use crate::grid::Dir;
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Parsed = Board;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        input.parse()
+    }
+
+    fn part1(board: &Self::Parsed) -> Result<impl Display, SolveError> {
+        Ok(simulate(
+            board,
+            Ray {
+                pos: Pos::new(0, 0),
+                dir: Dir::Right,
+            },
+        ))
+    }
 
-    let res = simulate_all(&board);
-    dbg!(res);
+    fn part2(board: &Self::Parsed) -> Result<impl Display, SolveError> {
+        Ok(simulate_all(board))
+    }
 }
 
 #[derive(Debug, PartialEq)]
-enum Tile {
+pub enum Tile {
     Empty,
     RightSlash,         // \
     LeftSlash,          // /
@@ -27,65 +43,41 @@ enum Tile {
     HorizontalSplitter, // |
 }
 
-impl Tile {
-    fn from_char(c: char) -> Self {
+impl Cell for Tile {
+    const EXPECTED: &'static str = "a tile";
+
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '.' => Tile::Empty,
-            '\\' => Tile::LeftSlash,
-            '/' => Tile::RightSlash,
-            '-' => Tile::HorizontalSplitter,
-            '|' => Tile::VerticalSplitter,
-            _ => panic!("bad input {c}"),
+            '.' => Some(Tile::Empty),
+            '\\' => Some(Tile::LeftSlash),
+            '/' => Some(Tile::RightSlash),
+            '-' => Some(Tile::HorizontalSplitter),
+            '|' => Some(Tile::VerticalSplitter),
+            _ => None,
         }
     }
-}
-
-#[derive(Debug, PartialEq)]
-struct Board {
-    tiles: Vec<Vec<Tile>>,
-    width: usize,
-    height: usize,
-}
-
-impl Board {
-    fn from_str(input: &str) -> Self {
-        let tiles = input
-            .lines()
-            .map(|l| l.trim().chars().map(Tile::from_char).collect::<Vec<_>>())
-            .collect::<Vec<_>>();
 
-        let height = tiles.len();
-        let width = tiles[0].len();
-
-        Self {
-            tiles,
-            height,
-            width,
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Empty => '.',
+            Tile::RightSlash => '/',
+            Tile::LeftSlash => '\\',
+            Tile::VerticalSplitter => '|',
+            Tile::HorizontalSplitter => '-',
         }
     }
-
-    fn get_tile(&self, x: usize, y: usize) -> &Tile {
-        &self.tiles[y][x]
-    }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-enum Dir {
-    Up,
-    Left,
-    Down,
-    Right,
-}
+pub type Board = Grid<Tile>;
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
-struct Ray {
-    x: usize,
-    y: usize,
-    dir: Dir,
+pub struct Ray {
+    pub pos: Pos,
+    pub dir: Dir,
 }
 
-fn step(board: &Board, ray: Ray) -> Vec<Ray> {
-    let tile = board.get_tile(ray.x, ray.y);
+pub fn step(board: &Board, ray: Ray) -> Vec<Ray> {
+    let tile = &board[ray.pos];
 
     let next_dirs = match tile {
         Tile::Empty => vec![ray.dir],
@@ -116,113 +108,52 @@ fn step(board: &Board, ray: Ray) -> Vec<Ray> {
     };
 
     next_dirs
-        .iter()
-        .filter_map(|dir| match dir {
-            Dir::Up if ray.y > 0 => Some(Ray {
-                x: ray.x,
-                y: ray.y - 1,
-                dir: *dir,
-            }),
-            Dir::Left if ray.x > 0 => Some(Ray {
-                x: ray.x - 1,
-                y: ray.y,
-                dir: *dir,
-            }),
-            Dir::Down if ray.y < board.height - 1 => Some(Ray {
-                x: ray.x,
-                y: ray.y + 1,
-                dir: *dir,
-            }),
-            Dir::Right if ray.x < board.width - 1 => Some(Ray {
-                x: ray.x + 1,
-                y: ray.y,
-                dir: *dir,
-            }),
-            _ => None,
+        .into_iter()
+        .filter_map(|dir| {
+            Some(Ray {
+                pos: board.step(ray.pos, dir)?,
+                dir,
+            })
         })
         .collect()
 }
 
-fn debug(board: &Board, ray: &Ray) {
-    for (row_i, row) in board.tiles.iter().enumerate() {
-        for (col_i, tile) in row.iter().enumerate() {
-            let c = if ray.x == col_i && ray.y == row_i {
-                match ray.dir {
-                    Dir::Up => '^',
-                    Dir::Left => '<',
-                    Dir::Down => 'V',
-                    Dir::Right => '>',
-                }
-            } else {
-                match tile {
-                    Tile::Empty => '.',
-                    Tile::RightSlash => '/',
-                    Tile::LeftSlash => '\\',
-                    Tile::VerticalSplitter => '|',
-                    Tile::HorizontalSplitter => '-',
-                }
-            };
-            print!("{}", c);
-        }
-        println!();
-    }
-}
-
-fn simulate_all(board: &Board) -> usize {
-    let mut scores = Vec::new();
-
-    for x in 0..board.width {
-        let score = simulate(
-            &board,
-            Ray {
-                x,
-                y: 0,
-                dir: Dir::Down,
-            },
-        );
-
-        scores.push(score);
-
-        let score = simulate(
-            &board,
-            Ray {
-                x,
-                y: board.height - 1,
-                dir: Dir::Up,
-            },
-        );
-
-        scores.push(score);
-    }
-
-    for y in 0..board.height {
-        let score = simulate(
-            &board,
-            Ray {
-                x: 0,
-                y: y,
-                dir: Dir::Right,
-            },
-        );
-
-        scores.push(score);
-
-        let score = simulate(
-            &board,
-            Ray {
-                x: board.width - 1,
-                y: y,
-                dir: Dir::Left,
-            },
-        );
+// the board with the ray drawn on it, only rendered when trace events of day 16 are logged
+fn trace_ray(board: &Board, ray: &Ray) {
+    let arrow = match ray.dir {
+        Dir::Up => '^',
+        Dir::Left => '<',
+        Dir::Down => 'V',
+        Dir::Right => '>',
+    };
 
-        scores.push(score);
-    }
+    trace!(
+        "ray at {:?}\n{}",
+        ray.pos,
+        board.render(|pos, tile| if pos == ray.pos {
+            arrow
+        } else {
+            tile.to_char()
+        })
+    );
+}
 
-    *scores.iter().max().unwrap()
+pub fn simulate_all(board: &Board) -> usize {
+    // rays enter from every edge, heading away from it
+    Dir::ALL
+        .into_iter()
+        .flat_map(|edge| {
+            board.edge(edge).map(move |pos| Ray {
+                pos,
+                dir: edge.opposite(),
+            })
+        })
+        .map(|ray| simulate(board, ray))
+        .max()
+        .unwrap()
 }
 
-fn simulate(board: &Board, start_ray: Ray) -> usize {
+pub fn simulate(board: &Board, start_ray: Ray) -> usize {
     let mut visited = HashSet::new();
 
     let mut rays = vec![start_ray];
@@ -234,51 +165,56 @@ fn simulate(board: &Board, start_ray: Ray) -> usize {
 
         visited.insert(ray.clone());
 
-        // debug(&board, &ray);
-        let next_rays = step(&board, ray);
+        trace_ray(board, &ray);
+        let next_rays = step(board, ray);
         for next in next_rays {
             rays.push(next);
         }
     }
 
-    visited
-        .iter()
-        .map(|r| (r.x, r.y))
-        .collect::<HashSet<_>>()
-        .len()
+    visited.iter().map(|r| r.pos).collect::<HashSet<_>>().len()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
+    use proptest::prelude::*;
+
     use super::*;
 
+    #[test]
+    fn board_from_str_reports_ragged_row() {
+        let error = Board::from_str(".|..\n.-.\n").unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.found(), None);
+    }
+
     #[test]
     fn board_from_str_works() {
         let input = ".|.\\
 .|-/";
 
-        let board = Board::from_str(input);
+        let board = Board::from_str(input).unwrap();
 
         assert_eq!(
             board,
-            Board {
-                width: 4,
-                height: 2,
-                tiles: vec![
-                    vec![
-                        Tile::Empty,
-                        Tile::VerticalSplitter,
-                        Tile::Empty,
-                        Tile::LeftSlash
-                    ],
-                    vec![
-                        Tile::Empty,
-                        Tile::VerticalSplitter,
-                        Tile::HorizontalSplitter,
-                        Tile::RightSlash
-                    ]
+            Grid::from_rows(vec![
+                vec![
+                    Tile::Empty,
+                    Tile::VerticalSplitter,
+                    Tile::Empty,
+                    Tile::LeftSlash
+                ],
+                vec![
+                    Tile::Empty,
+                    Tile::VerticalSplitter,
+                    Tile::HorizontalSplitter,
+                    Tile::RightSlash
                 ]
-            }
+            ])
+            .unwrap()
         )
     }
 
@@ -295,13 +231,12 @@ mod tests {
         .|....-|.\\
         ..//.|....";
 
-        let board = Board::from_str(input);
+        let board = Board::from_str(input).unwrap();
 
         let res = simulate(
             &board,
             Ray {
-                x: 0,
-                y: 0,
+                pos: Pos::new(0, 0),
                 dir: Dir::Right,
             },
         );
@@ -322,10 +257,34 @@ mod tests {
         .|....-|.\\
         ..//.|....";
 
-        let board = Board::from_str(input);
+        let board = Board::from_str(input).unwrap();
 
         let res = simulate_all(&board);
 
         assert_eq!(res, 51);
     }
+
+    fn board() -> impl Strategy<Value = Board> {
+        (1..10usize, 1..10usize).prop_flat_map(|(width, height)| {
+            let row = prop::string::string_regex(&format!("[.|/\\\\-]{{{width}}}")).unwrap();
+
+            prop::collection::vec(row, height)
+                .prop_map(|rows| Board::from_str(&rows.join("\n")).unwrap())
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn simulate_stays_within_board(board in board(), start in any::<prop::sample::Index>()) {
+            let tiles = board.width() * board.height();
+            let pos = board.positions().nth(start.index(tiles)).unwrap();
+
+            for dir in Dir::ALL {
+                let energized = simulate(&board, Ray { pos, dir });
+
+                prop_assert!((1..=tiles).contains(&energized));
+            }
+            prop_assert!(simulate_all(&board) <= tiles);
+        }
+    }
 }
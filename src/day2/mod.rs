@@ -1,45 +1,63 @@
+use std::fmt::Display;
+
 use regex::Regex;
 
-pub fn solve() {
-    let input = include_str!("./input.txt");
-    let rules = GameRule {
-        max_reds: 12,
-        max_greens: 13,
-        max_blues: 14,
-    };
+use crate::{
+    error::{parse_lines, parse_number, ParseError, SolveError},
+    solution::Solution,
+};
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Parsed = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse_games(input)
+    }
+
+    fn part1(games: &Self::Parsed) -> Result<impl Display, SolveError> {
+        let rules = GameRule {
+            max_reds: 12,
+            max_greens: 13,
+            max_blues: 14,
+        };
 
-    let part1 = get_possible_games(input, rules);
-    dbg!(part1);
+        Ok(get_possible_games(games, rules))
+    }
 
-    let part2 = get_games_power(input);
-    dbg!(part2);
+    fn part2(games: &Self::Parsed) -> Result<impl Display, SolveError> {
+        get_games_power(games).ok_or(SolveError::Overflow)
+    }
 }
 
 #[derive(PartialEq, Debug)]
-struct Game {
-    id: u32,
-    reveals: Vec<BallSet>,
+pub struct Game {
+    pub id: u32,
+    pub reveals: Vec<BallSet>,
 }
 
 impl Game {
-    fn parse_str(input: &str) -> Self {
+    pub fn parse_str(input: &str) -> Result<Self, ParseError> {
         let regex = Regex::new(r"Game (\d+): (.*)").unwrap();
-        let parts = regex.captures(input).unwrap();
+        let parts = regex.captures(input).ok_or_else(|| {
+            ParseError::new(input, input.trim_start(), "\"Game <id>: <reveals>\"")
+        })?;
 
-        let game_id: u32 = parts.get(1).unwrap().as_str().parse().unwrap();
+        let game_id = parse_number(input, parts.get(1).unwrap().as_str())?;
         let details = parts.get(2).unwrap().as_str();
         let reveals = details
-            .split(";")
-            .map(BallSet::parse_str)
-            .collect::<Vec<_>>();
+            .split(';')
+            .map(|reveal| BallSet::parse_str(reveal).map_err(|e| e.within(input, reveal)))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Game {
+        Ok(Game {
             id: game_id,
             reveals,
-        }
+        })
     }
 
-    fn get_minimal_set(&self) -> BallSet {
+    pub fn get_minimal_set(&self) -> BallSet {
         let min_reds = self.reveals.iter().map(|r| r.reds).max().unwrap_or(0);
         let min_blues = self.reveals.iter().map(|r| r.blues).max().unwrap_or(0);
         let min_greens = self.reveals.iter().map(|r| r.greens).max().unwrap_or(0);
@@ -53,78 +71,85 @@ impl Game {
 }
 
 #[derive(PartialEq, Debug)]
-struct BallSet {
-    reds: u32,
-    blues: u32,
-    greens: u32,
+pub struct BallSet {
+    pub reds: u32,
+    pub blues: u32,
+    pub greens: u32,
 }
 
 impl BallSet {
-    fn parse_str(input: &str) -> Self {
-        let blues_regex = Regex::new(r"(\d+) blue").unwrap();
-        let blues = blues_regex
-            .captures(input)
-            .and_then(|c| c.get(1))
-            .and_then(|d| d.as_str().parse::<u32>().ok())
-            .unwrap_or(0);
-
-        let reds_regex = Regex::new(r"(\d+) red").unwrap();
-        let reds = reds_regex
-            .captures(input)
-            .and_then(|c| c.get(1))
-            .and_then(|d| d.as_str().parse::<u32>().ok())
-            .unwrap_or(0);
-
-        let greens_regex = Regex::new(r"(\d+) green").unwrap();
-        let greens = greens_regex
-            .captures(input)
-            .and_then(|c| c.get(1))
-            .and_then(|d| d.as_str().parse::<u32>().ok())
-            .unwrap_or(0);
+    // "3 blue, 4 red", colors that are not revealed count as 0
+    pub fn parse_str(input: &str) -> Result<Self, ParseError> {
+        let mut set = BallSet {
+            reds: 0,
+            blues: 0,
+            greens: 0,
+        };
 
-        BallSet {
-            blues,
-            reds,
-            greens,
+        for balls in input.split(',') {
+            let balls = balls.trim();
+            let (count, color) = balls
+                .split_once(' ')
+                .ok_or_else(|| ParseError::new(input, balls, "\"<count> <color>\""))?;
+
+            let count = parse_number(input, count)?;
+
+            match color {
+                "red" => set.reds = count,
+                "green" => set.greens = count,
+                "blue" => set.blues = count,
+                _ => {
+                    return Err(ParseError::new(
+                        input,
+                        color,
+                        "\"red\", \"green\" or \"blue\"",
+                    ))
+                }
+            }
         }
+
+        Ok(set)
     }
 
-    fn get_power(&self) -> u32 {
-        self.reds * self.greens * self.blues
+    /// `None` when the power overflows.
+    pub fn get_power(&self) -> Option<u64> {
+        u64::from(self.reds)
+            .checked_mul(self.greens.into())?
+            .checked_mul(self.blues.into())
     }
 }
 
-struct GameRule {
-    max_reds: u32,
-    max_blues: u32,
-    max_greens: u32,
+pub struct GameRule {
+    pub max_reds: u32,
+    pub max_blues: u32,
+    pub max_greens: u32,
 }
 
 impl GameRule {
-    fn is_game_possible(&self, game: &Game) -> bool {
+    pub fn is_game_possible(&self, game: &Game) -> bool {
         game.reveals.iter().all(|r| {
             r.reds <= self.max_reds && r.greens <= self.max_greens && r.blues <= self.max_blues
         })
     }
 }
 
-fn parse_games(input: &str) -> impl Iterator<Item = Game> + '_ {
-    input.split("\n").map(Game::parse_str)
+pub fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
+    parse_lines(input, Game::parse_str)
 }
 
-fn get_possible_games(input: &str, game_rules: GameRule) -> u32 {
-    let games = parse_games(input);
-
+pub fn get_possible_games(games: &[Game], game_rules: GameRule) -> u64 {
     games
-        .filter(|game| game_rules.is_game_possible(&game))
-        .map(|game| game.id)
+        .iter()
+        .filter(|game| game_rules.is_game_possible(game))
+        .map(|game| u64::from(game.id))
         .sum()
 }
 
-fn get_games_power(input: &str) -> u32 {
-    let games = parse_games(input);
-
-    games.map(|game| game.get_minimal_set().get_power()).sum()
+/// `None` when the sum overflows.
+pub fn get_games_power(games: &[Game]) -> Option<u64> {
+    games.iter().try_fold(0u64, |sum, game| {
+        sum.checked_add(game.get_minimal_set().get_power()?)
+    })
 }
 
 #[cfg(test)]
@@ -134,7 +159,7 @@ mod tests {
     #[test]
     fn parse_games_valid_input_returns_game() {
         let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-        let actual = Game::parse_str(input);
+        let actual = Game::parse_str(input).unwrap();
 
         assert_eq!(
             actual,
@@ -161,6 +186,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_games_reports_bad_color() {
+        let input = "Game 1: 3 blue, 4 red
+Game 2: 1 blue; 2 purple";
+        let error = parse_games(input).unwrap_err();
+
+        assert_eq!(error.line, 2);
+        assert_eq!(error.column, 19);
+        assert_eq!(error.expected, "\"red\", \"green\" or \"blue\"");
+    }
+
     #[test]
     fn game_get_minimal_set() {
         let game = Game {
@@ -210,7 +246,7 @@ mod tests {
             max_blues: 14,
         };
 
-        assert_eq!(get_possible_games(input, rules), 8);
+        assert_eq!(get_possible_games(&parse_games(input).unwrap(), rules), 8);
     }
 
     #[test]
@@ -221,6 +257,6 @@ mod tests {
     Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
     Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
-        assert_eq!(get_games_power(input), 2286);
+        assert_eq!(get_games_power(&parse_games(input).unwrap()), Some(2286));
     }
 }
@@ -1,51 +1,74 @@
-use regex::Regex;
+use std::fmt::Display;
 
-pub fn solve() {
-    let input = include_str!("./input.txt");
-    let res = get_part_number_sum(input);
+use crate::{
+    error::{parse_number, ParseError, SolveError},
+    grid::{Grid, Pos},
+    solution::Solution,
+};
 
-    dbg!(res);
+pub struct Day3;
 
-    let res = get_gear_ratio_sum(input);
-    dbg!(res);
+impl Solution for Day3 {
+    type Parsed = Board;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(board: &Self::Parsed) -> Result<impl Display, SolveError> {
+        Ok(get_part_number_sum(board))
+    }
+
+    fn part2(board: &Self::Parsed) -> Result<impl Display, SolveError> {
+        get_gear_ratio_sum(board).ok_or(SolveError::Overflow)
+    }
 }
 
-fn get_part_number_sum(input: &str) -> u32 {
-    let board = parse(input);
+pub fn get_part_number_sum(board: &Board) -> u64 {
     let part_numbers = board.get_part_numbers();
 
-    part_numbers.iter().sum()
+    part_numbers.iter().map(|&n| u64::from(n)).sum()
 }
 
-fn get_gear_ratio_sum(input: &str) -> u32 {
-    let board = parse(input);
+/// `None` when the sum overflows.
+pub fn get_gear_ratio_sum(board: &Board) -> Option<u64> {
     let gears = board.get_gears();
 
-    gears.iter().map(|g| g.1 * g.2).sum()
+    gears.iter().try_fold(0u64, |sum, g| {
+        sum.checked_add(u64::from(g.1) * u64::from(g.2))
+    })
 }
 
-#[derive(Debug, PartialEq)]
-struct Coord(i32, i32);
-
 #[derive(Debug, PartialEq, Clone)]
-struct SymbolPos {
-    symbol: char,
-    x: i32,
-    y: i32,
+pub struct SymbolPos {
+    pub symbol: char,
+    pub pos: Pos,
 }
 
 #[derive(Debug, PartialEq)]
-struct NumberPos {
-    number: u32,
-    start_x: i32,
-    end_x: i32,
-    y: i32,
+pub struct NumberPos {
+    pub number: u32,
+    pub start_x: usize,
+    pub end_x: usize,
+    pub y: usize,
+}
+
+impl NumberPos {
+    /// The positions covered by the digits of the number.
+    pub fn cells(&self) -> impl Iterator<Item = Pos> + '_ {
+        (self.start_x..=self.end_x).map(|x| Pos::new(x, self.y))
+    }
 }
 
 #[derive(Debug, PartialEq)]
-struct Board {
-    symbols: Vec<SymbolPos>,
-    numbers: Vec<NumberPos>,
+pub struct Board {
+    pub grid: Grid<char>,
+    pub symbols: Vec<SymbolPos>,
+    pub numbers: Vec<NumberPos>,
+}
+
+fn is_symbol(c: char) -> bool {
+    !c.is_ascii_digit() && c != '.'
 }
 
 impl Board {
@@ -62,17 +85,20 @@ impl Board {
         //   .12.
         //   ....
 
-        self.symbols
-            .iter()
-            .any(|s| self.is_near_symbol(number_pos, s))
+        self.surroundings(number_pos)
+            .any(|pos| is_symbol(self.grid[pos]))
     }
 
     pub fn is_near_symbol(&self, number_pos: &NumberPos, symbol_pos: &SymbolPos) -> bool {
-        (number_pos.start_x - 1 == symbol_pos.x && number_pos.y == symbol_pos.y) // symbol on the left
-                || (number_pos.end_x + 1 == symbol_pos.x && number_pos.y == symbol_pos.y) // symbol on the right
-                || (number_pos.start_x - 1 <= symbol_pos.x && number_pos.end_x + 1 >= symbol_pos.x && number_pos.y - 1 == symbol_pos.y) // symbol above
-                || (number_pos.start_x - 1 <= symbol_pos.x && number_pos.end_x + 1 >= symbol_pos.x && number_pos.y + 1 == symbol_pos.y)
-        // symbol below
+        self.surroundings(number_pos)
+            .any(|pos| pos == symbol_pos.pos)
+    }
+
+    // every position touching a digit of the number, diagonals included
+    fn surroundings<'a>(&'a self, number_pos: &'a NumberPos) -> impl Iterator<Item = Pos> + 'a {
+        number_pos
+            .cells()
+            .flat_map(|cell| self.grid.neighbours8(cell))
     }
 
     pub fn get_gears(&self) -> Vec<(SymbolPos, u32, u32)> {
@@ -95,46 +121,44 @@ impl Board {
     }
 }
 
-fn parse(input: &str) -> Board {
-    let lines = input.split("\n");
-
-    let symbol_regex = Regex::new(r"[^0-9\.]").unwrap();
-    let number_regex = Regex::new(r"\d+").unwrap();
-
-    let symbols = lines
-        .clone()
-        .enumerate()
-        .flat_map(|(i, l)| {
-            symbol_regex
-                .find_iter(l)
-                .map(move |m| SymbolPos {
-                    symbol: m.as_str().chars().next().unwrap(),
-                    x: m.start() as i32,
-                    y: i as i32,
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect();
+pub fn parse(input: &str) -> Result<Board, ParseError> {
+    let grid: Grid<char> = input.parse()?;
 
-    let part_numbers = lines
-        .enumerate()
-        .flat_map(|(i, l)| {
-            number_regex
-                .find_iter(l)
-                .map(move |m| NumberPos {
-                    number: m.as_str().parse().unwrap(),
-                    start_x: m.start() as i32,
-                    end_x: m.end() as i32 - 1,
-                    y: i as i32,
-                })
-                .collect::<Vec<_>>()
-        })
+    let symbols = grid
+        .iter()
+        .filter(|(_, c)| is_symbol(**c))
+        .map(|(pos, c)| SymbolPos { symbol: *c, pos })
         .collect();
 
-    Board {
-        symbols,
-        numbers: part_numbers,
+    let mut numbers = vec![];
+
+    for (y, row) in grid.rows().enumerate() {
+        let line = row.iter().collect::<String>();
+        let mut x = 0;
+
+        for run in row.chunk_by(|a, b| a.is_ascii_digit() == b.is_ascii_digit()) {
+            if run[0].is_ascii_digit() {
+                // digits are a byte each, but the cells before them may not be
+                let start = line.char_indices().nth(x).map_or(line.len(), |(i, _)| i);
+
+                numbers.push(NumberPos {
+                    number: parse_number(&line, &line[start..start + run.len()])
+                        .map_err(|e| e.lines_down(y))?,
+                    start_x: x,
+                    end_x: x + run.len() - 1,
+                    y,
+                });
+            }
+
+            x += run.len();
+        }
     }
+
+    Ok(Board {
+        grid,
+        symbols,
+        numbers,
+    })
 }
 
 #[cfg(test)]
@@ -149,25 +173,22 @@ mod tests {
 617*......";
 
         let expected = Board {
+            grid: input.parse().unwrap(),
             symbols: vec![
                 SymbolPos {
-                    x: 3,
-                    y: 0,
+                    pos: Pos::new(3, 0),
                     symbol: '*',
                 },
                 SymbolPos {
-                    x: 6,
-                    y: 2,
+                    pos: Pos::new(6, 2),
                     symbol: '#',
                 },
                 SymbolPos {
-                    x: 7,
-                    y: 2,
+                    pos: Pos::new(7, 2),
                     symbol: '$',
                 },
                 SymbolPos {
-                    x: 3,
-                    y: 3,
+                    pos: Pos::new(3, 3),
                     symbol: '*',
                 },
             ],
@@ -193,7 +214,7 @@ mod tests {
             ],
         };
 
-        assert_eq!(expected, parse(input));
+        assert_eq!(expected, parse(input).unwrap());
     }
 
     #[test]
@@ -210,35 +231,26 @@ mod tests {
 .664.598..
 ";
 
-        let board = parse(input);
+        let board = parse(input).unwrap();
         dbg!(&board);
-        assert_eq!(
-            board.is_near_any_symbol(&NumberPos {
-                number: 58,
-                start_x: 7,
-                end_x: 8,
-                y: 5
-            }),
-            false
-        );
-        assert_eq!(
-            board.is_near_any_symbol(&NumberPos {
-                number: 35,
-                start_x: 2,
-                end_x: 3,
-                y: 2
-            }),
-            true
-        );
-        assert_eq!(
-            board.is_near_any_symbol(&NumberPos {
-                number: 633,
-                start_x: 6,
-                end_x: 8,
-                y: 2
-            }),
-            true
-        );
+        assert!(!board.is_near_any_symbol(&NumberPos {
+            number: 58,
+            start_x: 7,
+            end_x: 8,
+            y: 5
+        }));
+        assert!(board.is_near_any_symbol(&NumberPos {
+            number: 35,
+            start_x: 2,
+            end_x: 3,
+            y: 2
+        }));
+        assert!(board.is_near_any_symbol(&NumberPos {
+            number: 633,
+            start_x: 6,
+            end_x: 8,
+            y: 2
+        }));
     }
 
     #[test]
@@ -248,7 +260,7 @@ mod tests {
 ......#...
 617*......";
 
-        let board = parse(input);
+        let board = parse(input).unwrap();
         assert_eq!(board.get_part_numbers(), vec![633, 617]);
     }
 
@@ -265,7 +277,32 @@ mod tests {
 ...$.*....
 .664.598..
 ";
-        assert_eq!(get_part_number_sum(input), 4361);
+        assert_eq!(get_part_number_sum(&parse(input).unwrap()), 4361);
+    }
+
+    #[test]
+    fn parse_reports_number_overflow() {
+        let input = "..35.........
+.99999999999*";
+        let error = parse(input).unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
+    #[test]
+    fn parse_counts_columns_in_cells() {
+        let board = parse("é12*\n....").unwrap();
+
+        assert_eq!(
+            board.numbers,
+            vec![NumberPos {
+                number: 12,
+                start_x: 1,
+                end_x: 2,
+                y: 0
+            }]
+        );
+        assert_eq!(board.get_part_numbers(), vec![12]);
     }
 
     #[test]
@@ -281,6 +318,6 @@ mod tests {
 ...$.*....
 .664.598..
 ";
-        assert_eq!(get_gear_ratio_sum(input), 467835);
+        assert_eq!(get_gear_ratio_sum(&parse(input).unwrap()), Some(467835));
     }
 }
@@ -1,100 +1,133 @@
 use std::{
-    cmp::{max, min},
+    cmp::min,
     collections::{HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
 };
 
 use regex::Regex;
 
-pub fn solve() {
-    let input = include_str!("./input.txt");
-    let res = get_sum_scores(input);
+use crate::{
+    error::{parse_lines, parse_number, ParseError, SolveError},
+    solution::Solution,
+};
+
+pub struct Day4;
 
-    dbg!(res);
+impl Solution for Day4 {
+    type Parsed = Vec<Card>;
 
-    let res = part_2(input);
-    dbg!(res);
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse_cards(input)
+    }
+
+    fn part1(cards: &Self::Parsed) -> Result<impl Display, SolveError> {
+        get_sum_scores(cards).ok_or(SolveError::Overflow)
+    }
+
+    fn part2(cards: &Self::Parsed) -> Result<impl Display, SolveError> {
+        part_2(cards).ok_or(SolveError::Overflow)
+    }
 }
 
 #[derive(PartialEq, Debug)]
-struct Card {
-    id: u32,
-    numbers: HashSet<u32>,
-    winning_numbers: HashSet<u32>,
+pub struct Card {
+    pub id: u32,
+    pub numbers: HashSet<u32>,
+    pub winning_numbers: HashSet<u32>,
 }
 
-impl Card {
-    fn from_str(line: &str) -> Self {
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
         let card_regex = Regex::new(r"Card\s+(\d+): ([\d ]+) \| ([\d ]+)").unwrap();
         let number_regex = Regex::new(r"(\d+)").unwrap();
-        let captures = card_regex.captures(line).unwrap();
-        let id = captures
-            .get(1)
-            .and_then(|d| d.as_str().parse::<u32>().ok())
-            .unwrap();
+        let captures = card_regex.captures(line).ok_or_else(|| {
+            ParseError::new(
+                line,
+                line.trim_start(),
+                "\"Card <id>: <numbers> | <numbers>\"",
+            )
+        })?;
+        let id = parse_number(line, captures.get(1).unwrap().as_str())?;
 
         let numbers = captures.get(3).unwrap().as_str();
         let numbers = number_regex
             .find_iter(numbers)
-            .map(|n| n.as_str().parse::<u32>().unwrap())
-            .collect::<HashSet<_>>();
+            .map(|n| parse_number(line, n.as_str()))
+            .collect::<Result<HashSet<_>, _>>()?;
 
         let winning_numbers = captures.get(2).unwrap().as_str();
         let winning_numbers = number_regex
             .find_iter(winning_numbers)
-            .map(|n| n.as_str().parse::<u32>().unwrap())
-            .collect::<HashSet<_>>();
+            .map(|n| parse_number(line, n.as_str()))
+            .collect::<Result<HashSet<_>, _>>()?;
 
-        Card {
+        Ok(Card {
             id,
             numbers,
             winning_numbers,
-        }
+        })
     }
+}
 
-    fn get_winning_numbers_len(&self) -> u32 {
+impl Card {
+    pub fn get_winning_numbers_len(&self) -> u32 {
         self.numbers
             .iter()
             .filter(|n| self.winning_numbers.contains(n))
             .count() as u32
     }
 
-    fn get_score(&self) -> u32 {
+    /// `None` when the score overflows.
+    pub fn get_score(&self) -> Option<u64> {
         let n_winning = self.get_winning_numbers_len();
 
         if n_winning == 0 {
-            0
+            Some(0)
         } else {
-            2u32.pow(n_winning - 1)
+            2u64.checked_pow(n_winning - 1)
         }
     }
 }
 
-fn get_sum_scores(input: &str) -> u32 {
-    let cards = input.split("\n").map(Card::from_str).collect::<Vec<_>>();
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, ParseError> {
+    parse_lines(input, Card::from_str)
+}
 
-    cards.iter().map(|c| c.get_score()).sum()
+/// `None` when the sum overflows.
+pub fn get_sum_scores(cards: &[Card]) -> Option<u64> {
+    cards
+        .iter()
+        .try_fold(0u64, |sum, c| sum.checked_add(c.get_score()?))
 }
 
-fn part_2(input: &str) -> u32 {
-    let cards = input.split("\n").map(Card::from_str).collect::<Vec<_>>();
-    let cards_len = cards.len() as u32;
+/// `None` when the number of copies overflows.
+pub fn part_2(cards: &[Card]) -> Option<u64> {
+    let cards_len = cards.len() as u64;
 
-    let mut copies_by_card = cards.iter().map(|c| (c.id, 1)).collect::<HashMap<_, _>>();
+    let mut copies_by_card = cards
+        .iter()
+        .map(|c| (u64::from(c.id), 1u64))
+        .collect::<HashMap<_, _>>();
 
     for card in cards {
-        let w = card.get_winning_numbers_len();
-        let copies = copies_by_card.get(&card.id).unwrap().clone();
-
-        for _ in 0..copies {
-            for next_card_i in (card.id + 1)..(min(card.id + 1 + w, cards_len as u32 + 1)) {
-                copies_by_card
-                    .entry(next_card_i)
-                    .and_modify(|n| *n = *n + 1u32);
+        let id = u64::from(card.id);
+        let w = u64::from(card.get_winning_numbers_len());
+        let copies = copies_by_card[&id];
+
+        // every copy of this card wins one copy of each of the next `w` cards
+        for next_card_i in (id + 1)..(min(id + 1 + w, cards_len + 1)) {
+            if let Some(n) = copies_by_card.get_mut(&next_card_i) {
+                *n = n.checked_add(copies)?;
             }
         }
     }
 
-    copies_by_card.values().sum()
+    copies_by_card
+        .values()
+        .try_fold(0u64, |sum, n| sum.checked_add(*n))
 }
 
 #[cfg(test)]
@@ -111,15 +144,23 @@ mod tests {
             winning_numbers: HashSet::from([41, 48, 83, 86, 17]),
         };
 
-        assert_eq!(expected, Card::from_str(input));
+        assert_eq!(expected, Card::from_str(input).unwrap());
+    }
+
+    #[test]
+    fn card_from_str_reports_missing_separator() {
+        let error = Card::from_str("Card 1: 41 48 83 86 17").unwrap_err();
+
+        assert_eq!(error.column, 1);
+        assert_eq!(error.found(), Some("Card"));
     }
 
     #[test]
     fn card_get_score() {
         let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
-        let card = Card::from_str(input);
+        let card = Card::from_str(input).unwrap();
 
-        assert_eq!(card.get_score(), 8);
+        assert_eq!(card.get_score(), Some(8));
     }
 
     #[test]
@@ -131,7 +172,7 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
-        assert_eq!(get_sum_scores(input), 13);
+        assert_eq!(get_sum_scores(&parse_cards(input).unwrap()), Some(13));
     }
 
     #[test]
@@ -143,6 +184,6 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
-        assert_eq!(part_2(input), 30);
+        assert_eq!(part_2(&parse_cards(input).unwrap()), Some(30));
     }
 }
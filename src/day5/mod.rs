@@ -1,275 +1,618 @@
-use std::cmp;
-
-pub fn solve() {
-    let seeds = include_str!("./seeds.txt");
-    let seeds_to_soil = include_str!("./seed-to-soil.txt");
-    let soil_to_fert = include_str!("./soil-to-fert.txt");
-    let fert_to_water = include_str!("./fert-to-water.txt");
-    let water_to_light = include_str!("./water-to-light.txt");
-    let light_to_temp = include_str!("./light-to-temp.txt");
-    let temp_to_hum = include_str!("./temp-to-hum.txt");
-    let hum_to_loc = include_str!("./hum-to-loc.txt");
-
-    let res = part_2(
-        seeds,
-        seeds_to_soil,
-        soil_to_fert,
-        fert_to_water,
-        water_to_light,
-        light_to_temp,
-        temp_to_hum,
-        hum_to_loc,
-    );
-
-    dbg!(res);
-}
+use std::{
+    fmt::{self, Display},
+    iter,
+    ops::Range,
+    str::FromStr,
+};
 
-fn part_1(
-    seeds: &str,
-    seeds_to_soil: &str,
-    soil_to_fert: &str,
-    fert_to_water: &str,
-    water_to_light: &str,
-    light_to_temp: &str,
-    temp_to_hum: &str,
-    hum_to_loc: &str,
-) -> u64 {
-    let alamanc = Alamanc::parse(
-        seeds,
-        seeds_to_soil,
-        soil_to_fert,
-        fert_to_water,
-        water_to_light,
-        light_to_temp,
-        temp_to_hum,
-        hum_to_loc,
-    );
-
-    alamanc.get_min_loc()
-}
+use tracing::debug;
+
+use crate::{
+    error::{parse_lines, parse_number, ParseError, SolveError},
+    solution::Solution,
+};
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Parsed = Alamanc;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        input.parse()
+    }
+
+    fn part1(alamanc: &Self::Parsed) -> Result<impl Display, SolveError> {
+        alamanc
+            .get_min_loc()
+            .ok_or_else(|| SolveError::NoAnswer("there are no seeds".into()))
+    }
 
-fn part_2(
-    seeds: &str,
-    seeds_to_soil: &str,
-    soil_to_fert: &str,
-    fert_to_water: &str,
-    water_to_light: &str,
-    light_to_temp: &str,
-    temp_to_hum: &str,
-    hum_to_loc: &str,
-) -> u64 {
-    let alamanc = Alamanc::parse(
-        seeds,
-        seeds_to_soil,
-        soil_to_fert,
-        fert_to_water,
-        water_to_light,
-        light_to_temp,
-        temp_to_hum,
-        hum_to_loc,
-    );
-
-    alamanc.get_min_loc2()
+    fn part2(alamanc: &Self::Parsed) -> Result<impl Display, SolveError> {
+        alamanc.get_min_loc2()
+    }
 }
 
 #[derive(PartialEq, Debug, Clone)]
-struct MapRange {
-    source_start: u64,
-    dest_start: u64,
-    len: u64,
+pub struct MapRange {
+    pub source_start: u64,
+    pub dest_start: u64,
+    pub len: u64,
 }
 
-impl MapRange {
-    fn from_str(input: &str) -> Self {
-        let mut parts = input.split(" ");
+impl FromStr for MapRange {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parts = input.split_whitespace();
+        let end = &input[input.len()..];
 
-        let dest_start = parts.next().unwrap().parse::<u64>().unwrap();
-        let source_start = parts.next().unwrap().parse::<u64>().unwrap();
-        let length = parts.next().unwrap().parse::<u64>().unwrap();
+        let mut next = || parts.next().unwrap_or(end);
+        let dest_start = parse_number::<u64>(input, next())?;
+        let source_start = parse_number::<u64>(input, next())?;
+        let length = parse_number::<u64>(input, next())?;
+
+        if let Some(extra) = parts.next() {
+            return Err(ParseError::new(input, extra, "end of line"));
+        }
 
-        MapRange {
+        Ok(MapRange {
             source_start,
             dest_start,
             len: length,
-        }
+        })
     }
+}
 
-    fn get_mapping(&self, source: u64) -> Option<u64> {
+impl fmt::Display for MapRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.dest_start, self.source_start, self.len)
+    }
+}
+
+impl MapRange {
+    pub fn get_mapping(&self, source: u64) -> Option<u64> {
         if source >= self.source_start && source < self.source_start + self.len {
             Some(self.dest_start + (source - self.source_start))
         } else {
             None
         }
     }
+
+    /// The sources of the range that map into `dest`, `None` when there are none.
+    pub fn get_sources(&self, dest: Range<u64>) -> Option<Range<u64>> {
+        let start = dest.start.max(self.dest_start);
+        let end = dest.end.min(self.dest_start + self.len);
+
+        (start < end).then(|| {
+            self.source_start + (start - self.dest_start)
+                ..self.source_start + (end - self.dest_start)
+        })
+    }
 }
 
-#[derive(PartialEq, Debug)]
-struct MapRanges(Vec<MapRange>);
+#[derive(PartialEq, Debug, Clone)]
+pub struct MapRanges(pub Vec<MapRange>);
+
+/// One range per line, in the format of the input.
+impl fmt::Display for MapRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, range) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{range}")?;
+        }
 
-impl MapRanges {
-    fn from_str(input: &str) -> Self {
-        let ranges = input
-            .split('\n')
-            .map(MapRange::from_str)
+        Ok(())
+    }
+}
+
+/// A range of a map that can't be trusted, by index in the map, which is also its line within
+/// the map counted from 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    /// The range has a length of 0
+    Empty(usize),
+    /// `start + len` of the source or destination does not fit in a u64
+    Overflow(usize),
+    /// The sources of the ranges overlap, the earlier range comes first
+    Overlap(usize, usize),
+}
+
+impl FromStr for MapRanges {
+    type Err = ParseError;
+
+    // one range per line, rejecting the ranges `validate` reports. The error points at the
+    // first bad range and its message lists the others.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let ranges = Self(parse_lines(input, MapRange::from_str)?);
+
+        let line = |i: usize| input.split('\n').nth(i).unwrap_or_default();
+        // the source and length of the range on line `i`, which parsed
+        let token = |i: usize, n: usize| line(i).split_whitespace().nth(n).unwrap_or_default();
+
+        let mut errors = ranges.validate().into_iter().map(|error| match error {
+            RangeError::Empty(i) => (i, token(i, 2), "a length above 0".to_string()),
+            RangeError::Overflow(i) => (
+                i,
+                token(i, 2),
+                "a length that keeps the range within u64".to_string(),
+            ),
+            RangeError::Overlap(first, i) => (
+                i,
+                token(i, 1),
+                format!(
+                    "a source range that does not overlap \"{}\"",
+                    line(first).trim()
+                ),
+            ),
+        });
+
+        let Some((i, token, expected)) = errors.next() else {
+            return Ok(ranges);
+        };
+
+        let others = errors
+            .map(|(i, _, expected)| format!("\"{}\" needs {expected}", line(i).trim()))
             .collect::<Vec<_>>();
+        let expected = if others.is_empty() {
+            expected
+        } else {
+            format!("{expected} (also {})", others.join(", "))
+        };
 
-        Self(ranges)
+        Err(ParseError::new(line(i), token, expected).lines_down(i))
     }
+}
 
-    fn get_mapping(&self, source: u64) -> u64 {
-        let match_mapping = self
-            .0
-            .iter()
-            .filter_map(|range| range.get_mapping(source))
-            .collect::<Vec<_>>();
+impl MapRanges {
+    /// Every range that is empty, overflows or has sources overlapping an earlier one, by line.
+    pub fn validate(&self) -> Vec<RangeError> {
+        let mut errors = vec![];
+        // ranges that hold sources, to check for overlaps
+        let mut sound = vec![];
+
+        for (i, range) in self.0.iter().enumerate() {
+            if range.len == 0 {
+                errors.push(RangeError::Empty(i));
+            } else if range
+                .source_start
+                .max(range.dest_start)
+                .checked_add(range.len)
+                .is_none()
+            {
+                errors.push(RangeError::Overflow(i));
+            } else {
+                sound.push(i);
+            }
+        }
+
+        // in order of the sources, a range overlaps when it starts before the furthest end so far
+        sound.sort_by_key(|&i| (self.0[i].source_start, i));
 
-        assert!(match_mapping.len() <= 1);
+        let mut reach: Option<(u64, usize)> = None;
+        for i in sound {
+            let range = &self.0[i];
+            let end = range.source_start + range.len;
 
-        match match_mapping.first() {
-            Some(dest) => *dest,
-            _ => source,
+            if let Some((reach_end, j)) = reach {
+                if range.source_start < reach_end {
+                    errors.push(RangeError::Overlap(i.min(j), i.max(j)));
+                }
+            }
+            if reach.is_none_or(|(reach_end, _)| end > reach_end) {
+                reach = Some((end, i));
+            }
         }
+
+        errors.sort_by_key(|error| match *error {
+            RangeError::Empty(i) | RangeError::Overflow(i) | RangeError::Overlap(_, i) => i,
+        });
+        errors
     }
 
-    fn get_containing_range(&self, source: u64) -> Option<MapRange> {
+    /// An equivalent map with the ranges sorted by source and neighbours shifted by the same
+    /// amount merged into one. Ranges mapping numbers to themselves are left out and overlaps
+    /// are resolved like [`MapRanges::get_mapping`] does.
+    pub fn normalize(&self) -> MapRanges {
+        self.compose(&MapRanges(vec![]))
+    }
+
+    /// Where `source` maps to, itself when no range holds it. Parsing rejects overlapping
+    /// ranges, for others the first one holding `source` wins.
+    pub fn get_mapping(&self, source: u64) -> u64 {
         self.0
             .iter()
-            .find(|range| range.get_mapping(source).is_some())
-            .cloned()
+            .find_map(|range| range.get_mapping(source))
+            .unwrap_or(source)
+    }
+
+    /// Maps every number of `interval` at once, splitting it where the ranges start and end.
+    /// Numbers outside the ranges keep their value and the first range holding a number wins,
+    /// like in [`MapRanges::get_mapping`].
+    pub fn map_interval(&self, interval: Range<u64>) -> Vec<Range<u64>> {
+        self.split(interval)
+            .into_iter()
+            .map(|(part, dest)| dest..dest + (part.end - part.start))
+            .collect()
     }
+
+    /// Every source that maps into `dest`, sorted and merged. Numbers no range holds map to
+    /// themselves, so the parts of `dest` outside the ranges are sources too.
+    pub fn get_sources(&self, dest: Range<u64>) -> Vec<Range<u64>> {
+        let sources = self
+            .split(0..u64::MAX)
+            .into_iter()
+            .filter_map(|(part, start)| {
+                let range = MapRange {
+                    source_start: part.start,
+                    dest_start: start,
+                    len: part.end - part.start,
+                };
+                range.get_sources(dest.clone())
+            })
+            .collect();
+
+        merge(sources)
+    }
+
+    // the parts of `interval` that are shifted by a single amount, with where each one's start
+    // maps to. Parts taken by a range come first, in the order of the ranges.
+    fn split(&self, interval: Range<u64>) -> Vec<(Range<u64>, u64)> {
+        let mut mapped = vec![];
+        // parts of the interval no range has taken yet
+        let mut rest = vec![interval];
+
+        for range in &self.0 {
+            let source = range.source_start..range.source_start + range.len;
+            let mut outside = vec![];
+
+            for part in rest {
+                let start = part.start.max(source.start);
+                let end = part.end.min(source.end);
+
+                if start >= end {
+                    outside.push(part);
+                    continue;
+                }
+
+                mapped.push((start..end, range.dest_start + (start - source.start)));
+                outside.extend(
+                    [part.start..start, end..part.end]
+                        .into_iter()
+                        .filter(|part| !part.is_empty()),
+                );
+            }
+
+            rest = outside;
+        }
+
+        mapped.extend(rest.into_iter().map(|part| {
+            let start = part.start;
+            (part, start)
+        }));
+        mapped
+    }
+
+    /// One map doing what `self` and then `then` do. Its ranges are sorted by source, don't
+    /// overlap and leave out the numbers that keep their value.
+    pub fn compose(&self, then: &MapRanges) -> MapRanges {
+        let mut ranges = vec![];
+
+        // no range reaches u64::MAX, so it and every number kept out of the ranges keep their value
+        for (part, dest) in self.split(0..u64::MAX) {
+            for (then_part, then_dest) in then.split(dest..dest + (part.end - part.start)) {
+                let source_start = part.start + (then_part.start - dest);
+
+                if source_start != then_dest {
+                    ranges.push(MapRange {
+                        source_start,
+                        dest_start: then_dest,
+                        len: then_part.end - then_part.start,
+                    });
+                }
+            }
+        }
+
+        ranges.sort_by_key(|range| range.source_start);
+
+        // neighbours shifted by the same amount become one range
+        let mut joined: Vec<MapRange> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match joined.last_mut() {
+                Some(last)
+                    if last.source_start + last.len == range.source_start
+                        && last.dest_start + last.len == range.dest_start =>
+                {
+                    last.len += range.len
+                }
+                _ => joined.push(range),
+            }
+        }
+
+        MapRanges(joined)
+    }
+
+    /// [`MapRanges::map_interval`] for every interval, merged into as few as possible.
+    pub fn map_intervals(&self, intervals: &[Range<u64>]) -> Vec<Range<u64>> {
+        merge(
+            intervals
+                .iter()
+                .flat_map(|interval| self.map_interval(interval.clone()))
+                .collect(),
+        )
+    }
+}
+
+/// Sorts `intervals` and joins the ones that overlap or touch, dropping empty ones.
+pub fn merge(mut intervals: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    intervals.retain(|interval| !interval.is_empty());
+    intervals.sort_by_key(|interval| interval.start);
+
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+            _ => merged.push(interval),
+        }
+    }
+
+    merged
+}
+
+/// A map with ranges sorted by source that don't overlap, looked up with a binary search.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Table(MapRanges);
+
+impl Table {
+    pub fn new(ranges: &MapRanges) -> Self {
+        Table(ranges.normalize())
+    }
+
+    /// Where `source` maps to, like [`MapRanges::get_mapping`].
+    pub fn get_mapping(&self, source: u64) -> u64 {
+        let ranges = &self.0 .0;
+        let after = ranges.partition_point(|range| range.source_start <= source);
+
+        after
+            .checked_sub(1)
+            .and_then(|i| ranges[i].get_mapping(source))
+            .unwrap_or(source)
+    }
+
+    pub fn ranges(&self) -> &MapRanges {
+        &self.0
+    }
+}
+
+/// The ranges in the format of the input.
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A `<source>-to-<destination> map:` section of the almanac.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Stage {
+    pub source: String,
+    pub dest: String,
+    pub ranges: MapRanges,
 }
 
 #[derive(PartialEq, Debug)]
-struct Alamanc {
-    seeds: Vec<u64>,
-    seeds_to_soil: MapRanges,
-    soil_to_fert: MapRanges,
-    fert_to_water: MapRanges,
-    water_to_light: MapRanges,
-    light_to_temp: MapRanges,
-    temp_to_hum: MapRanges,
-    hum_to_loc: MapRanges,
+pub struct Alamanc {
+    pub seeds: Vec<u64>,
+    /// Every stage maps the category the one before maps to, starting with [`SEED`]
+    pub stages: Vec<Stage>,
 }
 
-impl Alamanc {
-    fn parse(
-        seeds: &str,
-        seeds_to_soil: &str,
-        soil_to_fert: &str,
-        fert_to_water: &str,
-        water_to_light: &str,
-        light_to_temp: &str,
-        temp_to_hum: &str,
-        hum_to_loc: &str,
-    ) -> Self {
-        let seeds = seeds
-            .split(' ')
-            .map(|n| n.parse::<u64>().unwrap())
-            .collect();
+/// The category of the seeds, where the chain of stages starts.
+pub const SEED: &str = "seed";
+
+impl FromStr for Alamanc {
+    type Err = ParseError;
+
+    // seeds line followed by the maps, each section separated by a blank line and starting
+    // with an "x-to-y map:" header
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut sections = input.split("\n\n");
+        let seeds_line = sections.next().unwrap_or_default();
+        let seeds = seeds_line
+            .strip_prefix("seeds: ")
+            .ok_or_else(|| ParseError::new(seeds_line, seeds_line, "\"seeds: \""))?;
+        let seeds = parse_seeds(seeds).map_err(|e| e.within(seeds_line, seeds))?;
+
+        // line of the input each section starts on, counted from 0
+        let mut line = 0;
+        let mut last_line = seeds_line;
+        let mut stages: Vec<Stage> = vec![];
+
+        for section in sections {
+            line += last_line.split('\n').count() + 1;
+            last_line = section;
+
+            let header = section.split('\n').next().unwrap_or_default();
+            let Some((source, dest)) = parse_header(header) else {
+                return Err(ParseError::new(
+                    section,
+                    section,
+                    "\"<source>-to-<destination> map:\"",
+                )
+                .lines_down(line));
+            };
+
+            // the chain goes on from the category it reached, and never comes back to one
+            let category = stages.last().map_or(SEED, |stage| stage.dest.as_str());
+            if source != category {
+                return Err(
+                    ParseError::new(header, source, format!("\"{category}\"")).lines_down(line)
+                );
+            }
+            if dest == SEED || stages.iter().any(|stage| stage.dest == dest) {
+                return Err(ParseError::new(
+                    header,
+                    dest,
+                    "a category that is not in the chain yet",
+                )
+                .lines_down(line));
+            }
+
+            let Some((_, ranges)) = section.split_once('\n') else {
+                return Err(
+                    ParseError::new(header, &header[header.len()..], "map ranges").lines_down(line),
+                );
+            };
+
+            stages.push(Stage {
+                source: source.to_string(),
+                dest: dest.to_string(),
+                ranges: ranges
+                    .parse()
+                    .map_err(|e: ParseError| e.lines_down(line + 1))?,
+            });
+        }
 
-        Self {
-            seeds,
-            seeds_to_soil: MapRanges::from_str(seeds_to_soil),
-            soil_to_fert: MapRanges::from_str(soil_to_fert),
-            fert_to_water: MapRanges::from_str(fert_to_water),
-            water_to_light: MapRanges::from_str(water_to_light),
-            light_to_temp: MapRanges::from_str(light_to_temp),
-            temp_to_hum: MapRanges::from_str(temp_to_hum),
-            hum_to_loc: MapRanges::from_str(hum_to_loc),
+        if stages.is_empty() {
+            return Err(ParseError::new(
+                seeds_line,
+                &seeds_line[seeds_line.len()..],
+                "at least one map",
+            ));
         }
+
+        Ok(Alamanc { seeds, stages })
     }
+}
 
-    fn get_loc(&self, seed: u64) -> u64 {
-        let soil = self.seeds_to_soil.get_mapping(seed);
-        let fert = self.soil_to_fert.get_mapping(soil);
-        let water = self.fert_to_water.get_mapping(fert);
-        let light = self.water_to_light.get_mapping(water);
-        let temp = self.light_to_temp.get_mapping(light);
-        let hum = self.temp_to_hum.get_mapping(temp);
-        let loc = self.hum_to_loc.get_mapping(hum);
+// the source and destination of a "<source>-to-<destination> map:" header, as slices of it
+fn parse_header(header: &str) -> Option<(&str, &str)> {
+    let (source, dest) = header.strip_suffix(" map:")?.split_once("-to-")?;
+    let is_name = |name: &str| !name.is_empty() && name.chars().all(char::is_alphanumeric);
+
+    (is_name(source) && is_name(dest)).then_some((source, dest))
+}
 
-        loc
+fn parse_seeds(seeds: &str) -> Result<Vec<u64>, ParseError> {
+    seeds
+        .split_whitespace()
+        .map(|n| parse_number(seeds, n))
+        .collect()
+}
+
+impl Alamanc {
+    /// Every category in the order of the chain, from [`SEED`] on.
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        iter::once(SEED).chain(self.stages.iter().map(|stage| stage.dest.as_str()))
     }
 
-    fn get_min_loc(&self) -> u64 {
-        self.seeds.iter().map(|s| self.get_loc(*s)).min().unwrap()
+    /// The stages turning category `from` into `to`, `None` when either is not in the chain or
+    /// `to` comes before `from`.
+    pub fn get_stages(&self, from: &str, to: &str) -> Option<&[Stage]> {
+        let from = self.categories().position(|category| category == from)?;
+        let to = self.categories().position(|category| category == to)?;
+
+        self.stages.get(from..to)
     }
 
-    fn get_next_range_equal_len(&self, seed: u64) -> u64 {
-        let Some(soil_range) = self.seeds_to_soil.get_containing_range(seed) else {return 1};
+    /// What `value` of category `from` is in category `to`, like the humidity of a seed with
+    /// `convert("seed", "humidity", seed)`.
+    pub fn convert(&self, from: &str, to: &str, value: u64) -> Option<u64> {
+        let stages = self.get_stages(from, to)?;
 
-        let soil = soil_range.get_mapping(seed).unwrap();
-        let remining_soil_range = soil_range.dest_start + soil_range.len - soil;
+        Some(
+            stages
+                .iter()
+                .fold(value, |value, stage| stage.ranges.get_mapping(value)),
+        )
+    }
 
-        let Some(fert_range) = self.soil_to_fert.get_containing_range(soil) else {return 1};
-        let fret = fert_range.get_mapping(soil).unwrap();
-        let remining_fert_range = fert_range.dest_start + fert_range.len - fret;
+    /// [`Alamanc::convert`] for every number of `interval` at once, sorted and merged.
+    pub fn convert_interval(
+        &self,
+        from: &str,
+        to: &str,
+        interval: Range<u64>,
+    ) -> Option<Vec<Range<u64>>> {
+        let stages = self.get_stages(from, to)?;
+
+        Some(stages.iter().fold(vec![interval], |intervals, stage| {
+            stage.ranges.map_intervals(&intervals)
+        }))
+    }
 
-        let Some(water_range) = self.fert_to_water.get_containing_range(fret) else {return 1};
-        let water = water_range.get_mapping(fret).unwrap();
-        let remining_water_range = water_range.dest_start + water_range.len - water;
+    /// Where `seed` ends up in the last category of the chain, its location.
+    pub fn get_loc(&self, seed: u64) -> u64 {
+        self.stages
+            .iter()
+            .fold(seed, |value, stage| stage.ranges.get_mapping(value))
+    }
 
-        let Some(light_range) = self.water_to_light.get_containing_range(water) else {return 1};
-        let light = light_range.get_mapping(water).unwrap();
-        let remining_light_range = light_range.dest_start + light_range.len - light;
+    /// Every stage folded into a single seed to location table.
+    pub fn get_table(&self) -> Table {
+        let composed = self
+            .stages
+            .iter()
+            .fold(MapRanges(vec![]), |composed, stage| {
+                composed.compose(&stage.ranges)
+            });
 
-        let Some(temp_range) = self.light_to_temp.get_containing_range(light) else {return 1};
-        let temp = temp_range.get_mapping(light).unwrap();
-        let remining_temp_range = temp_range.dest_start + temp_range.len - temp;
+        Table(composed)
+    }
 
-        let Some(hum_range) = self.temp_to_hum.get_containing_range(temp) else {return 1};
-        let hum = hum_range.get_mapping(temp).unwrap();
-        let remining_hum_range = hum_range.dest_start + hum_range.len - hum;
+    /// Every seed whose location is in `locs`, sorted and merged.
+    pub fn get_seeds(&self, locs: Range<u64>) -> Vec<Range<u64>> {
+        self.stages.iter().rev().fold(vec![locs], |dests, stage| {
+            merge(
+                dests
+                    .into_iter()
+                    .flat_map(|dest| stage.ranges.get_sources(dest))
+                    .collect(),
+            )
+        })
+    }
 
-        let Some(loc_range) = self.hum_to_loc.get_containing_range(hum) else {return 1};
-        let loc = loc_range.get_mapping(hum).unwrap();
-        let remining_loc_range = loc_range.dest_start + loc_range.len - loc;
+    /// `None` when there are no seeds.
+    pub fn get_min_loc(&self) -> Option<u64> {
+        let table = self.get_table();
 
-        [
-            remining_soil_range,
-            remining_fert_range,
-            remining_water_range,
-            remining_light_range,
-            remining_temp_range,
-            remining_hum_range,
-            remining_loc_range,
-        ]
-        .iter()
-        .min()
-        .unwrap()
-        .clone()
+        self.seeds.iter().map(|s| table.get_mapping(*s)).min()
     }
 
-    fn get_min_loc2(&self) -> u64 {
-        self.seeds
+    /// The seeds are read as pairs of range start and length.
+    pub fn get_min_loc2(&self) -> Result<u64, SolveError> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(SolveError::NoAnswer(
+                "the seeds do not come in pairs".into(),
+            ));
+        }
+
+        let seeds = self
+            .seeds
             .chunks(2)
-            .map(|c| {
-                dbg!(c);
-                let start = c[0];
-                let len = c[1];
-
-                let mut cur = start;
-                let mut min = u64::MAX;
-
-                while cur < start + len {
-                    let cur_loc = self.get_loc(cur);
-                    min = cmp::min(cur_loc, min);
-                    let jump = self.get_next_range_equal_len(cur);
-                    if jump > 1 {
-                        dbg!(jump);
-                    }
-                    cur = cur + jump;
-                }
+            .map(|c| Ok(c[0]..c[0].checked_add(c[1]).ok_or(SolveError::Overflow)?))
+            .collect::<Result<Vec<_>, SolveError>>()?;
+
+        // every stage maps whole intervals, so the work grows with the ranges and not the seeds
+        let mut intervals = merge(seeds);
+        for stage in &self.stages {
+            intervals = stage.ranges.map_intervals(&intervals);
+            debug!(stage = %stage.dest, intervals = intervals.len(), "mapped a stage");
+        }
 
-                min
-            })
-            .min()
-            .unwrap()
+        intervals
+            .first()
+            .map(|interval| interval.start)
+            .ok_or_else(|| SolveError::NoAnswer("there are no seeds".into()))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -308,11 +651,194 @@ mod tests {
         assert_eq!(ranges.get_mapping(100), 100);
     }
 
+    #[test]
+    fn map_range_get_sources_works() {
+        let range = MapRange {
+            source_start: 98,
+            dest_start: 50,
+            len: 2,
+        };
+
+        assert_eq!(range.get_sources(0..50), None);
+        assert_eq!(range.get_sources(51..60), Some(99..100));
+        assert_eq!(range.get_sources(40..60), Some(98..100));
+    }
+
+    #[test]
+    fn map_ranges_get_sources_works() {
+        let ranges = MapRanges::from_str("50 98 2\n52 50 48").unwrap();
+
+        assert_eq!(ranges.get_sources(50..51), vec![98..99]);
+        assert_eq!(ranges.get_sources(45..53), vec![45..51, 98..100]);
+        assert_eq!(ranges.get_sources(99..102), vec![97..98, 100..102]);
+    }
+
+    #[test]
+    fn map_ranges_map_interval_works() {
+        let ranges = MapRanges::from_str("50 98 2\n52 50 48").unwrap();
+
+        assert_eq!(ranges.map_interval(40..45), vec![40..45]);
+        assert_eq!(ranges.map_interval(79..93), vec![81..95]);
+        assert_eq!(
+            ranges.map_interval(45..105),
+            vec![50..52, 52..100, 45..50, 100..105]
+        );
+        assert_eq!(ranges.map_interval(96..99), vec![50..51, 98..100]);
+    }
+
+    #[test]
+    fn map_ranges_map_interval_prefers_the_first_range() {
+        let ranges = MapRanges(vec![
+            MapRange {
+                source_start: 0,
+                dest_start: 100,
+                len: 10,
+            },
+            MapRange {
+                source_start: 5,
+                dest_start: 200,
+                len: 10,
+            },
+        ]);
+
+        assert_eq!(ranges.map_interval(0..15), vec![100..110, 205..210]);
+    }
+
+    #[test]
+    fn map_ranges_compose_works() {
+        let seed_to_soil = MapRanges::from_str("50 98 2\n52 50 48").unwrap();
+        let soil_to_fert = MapRanges::from_str("0 15 37\n37 52 2\n39 0 15").unwrap();
+        let composed = seed_to_soil.compose(&soil_to_fert);
+
+        assert_eq!(
+            composed.to_string(),
+            "39 0 15\n0 15 35\n37 50 2\n54 52 46\n35 98 2"
+        );
+        for seed in 0..120 {
+            assert_eq!(
+                composed.get_mapping(seed),
+                soil_to_fert.get_mapping(seed_to_soil.get_mapping(seed))
+            );
+        }
+    }
+
+    #[test]
+    fn map_ranges_validate_works() {
+        let range = |source_start, dest_start, len| MapRange {
+            source_start,
+            dest_start,
+            len,
+        };
+        let ranges = MapRanges(vec![
+            range(10, 0, 5),
+            range(0, 0, 20),
+            range(30, 0, 0),
+            range(u64::MAX - 1, 0, 2),
+            range(40, 0, 5),
+            range(12, 100, 2),
+        ]);
+
+        assert_eq!(
+            ranges.validate(),
+            vec![
+                RangeError::Overlap(0, 1),
+                RangeError::Empty(2),
+                RangeError::Overflow(3),
+                RangeError::Overlap(1, 5),
+            ]
+        );
+        assert!(MapRanges::from_str("50 98 2\n52 50 48")
+            .unwrap()
+            .validate()
+            .is_empty());
+    }
+
+    #[test]
+    fn map_ranges_from_str_reports_bad_ranges() {
+        let error = MapRanges::from_str("50 98 2\n52 50 48\n0 60 5").unwrap_err();
+
+        assert_eq!((error.line, error.column), (3, 3));
+        assert_eq!(
+            error.expected,
+            "a source range that does not overlap \"52 50 48\""
+        );
+
+        let error = MapRanges::from_str("50 98 2\n52 50 0").unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.expected, "a length above 0");
+
+        let error = MapRanges::from_str("50 98 2\n0 18446744073709551615 2").unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 24));
+        assert_eq!(error.expected, "a length that keeps the range within u64");
+    }
+
+    #[test]
+    fn map_ranges_from_str_reports_every_bad_range() {
+        let error = MapRanges::from_str("50 98 0\n52 50 48\n0 60 5\n1 2 3").unwrap_err();
+
+        assert_eq!((error.line, error.column), (1, 7));
+        assert_eq!(
+            error.expected,
+            "a length above 0 (also \"0 60 5\" needs a source range that does not overlap \"52 50 48\")"
+        );
+    }
+
+    #[test]
+    fn map_ranges_normalize_works() {
+        let ranges = MapRanges::from_str("12 2 3\n10 0 2\n5 5 5\n0 20 1").unwrap();
+
+        assert_eq!(ranges.normalize().to_string(), "10 0 5\n0 20 1");
+    }
+
+    #[test]
+    fn map_ranges_display_round_trips() {
+        let ranges = MapRanges::from_str("50 98 2\n52 50 48").unwrap();
+
+        assert_eq!(MapRanges::from_str(&ranges.to_string()).unwrap(), ranges);
+    }
+
+    #[test]
+    fn alamanc_get_table_agrees_with_get_loc() {
+        let alamanc = Alamanc::from_str(EXAMPLE).unwrap();
+        let table = alamanc.get_table();
+
+        for seed in 0..120 {
+            assert_eq!(
+                table.get_mapping(seed),
+                alamanc.get_loc(seed),
+                "seed {seed}"
+            );
+        }
+        assert_eq!(table.get_mapping(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn alamanc_get_seeds_works() {
+        let alamanc = Alamanc::from_str(EXAMPLE).unwrap();
+        let seeds = alamanc.get_seeds(46..47);
+
+        assert!(seeds.contains(&(82..83)));
+        for seed in 0..120 {
+            assert_eq!(
+                seeds.iter().any(|seeds| seeds.contains(&seed)),
+                alamanc.get_loc(seed) == 46,
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn merge_works() {
+        assert_eq!(merge(vec![5..8, 0..2, 2..3, 7..9, 4..4]), vec![0..3, 5..9]);
+    }
+
     #[test]
     fn map_range_from_str_works() {
         let input = "50 98 2";
         assert_eq!(
-            MapRange::from_str(input),
+            MapRange::from_str(input).unwrap(),
             MapRange {
                 source_start: 98,
                 dest_start: 50,
@@ -321,89 +847,250 @@ mod tests {
         );
     }
 
-    #[test]
-    fn part_1_works() {
-        let seeds = "79 14 55 13";
+    const EXAMPLE: &str = "seeds: 79 14 55 13
 
-        let seed_to_soil = "50 98 2
-52 50 48";
+seed-to-soil map:
+50 98 2
+52 50 48
 
-        let soil_to_fertilizer = "0 15 37
+soil-to-fertilizer map:
+0 15 37
 37 52 2
-39 0 15";
+39 0 15
 
-        let fertilizer_to_water = "49 53 8
+fertilizer-to-water map:
+49 53 8
 0 11 42
 42 0 7
-57 7 4";
+57 7 4
 
-        let water_to_light = "88 18 7
-18 25 70";
+water-to-light map:
+88 18 7
+18 25 70
 
-        let light_to_temperature = "45 77 23
+light-to-temperature map:
+45 77 23
 81 45 19
-68 64 13";
+68 64 13
 
-        let temperature_to_humidity = "0 69 1
-1 0 69";
+temperature-to-humidity map:
+0 69 1
+1 0 69
 
-        let humidity_to_location = "60 56 37
+humidity-to-location map:
+60 56 37
 56 93 4";
 
-        let res = part_1(
-            seeds,
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location,
+    #[test]
+    fn alamanc_from_str_works() {
+        let alamanc = Alamanc::from_str(EXAMPLE).unwrap();
+
+        assert_eq!(alamanc.seeds, vec![79, 14, 55, 13]);
+        assert_eq!(
+            alamanc.stages[0],
+            Stage {
+                source: "seed".into(),
+                dest: "soil".into(),
+                ranges: MapRanges::from_str("50 98 2\n52 50 48").unwrap(),
+            }
+        );
+        assert_eq!(
+            alamanc.categories().collect::<Vec<_>>(),
+            [
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location"
+            ]
         );
+        assert_eq!(
+            alamanc.stages[6].ranges,
+            MapRanges::from_str("60 56 37\n56 93 4").unwrap()
+        );
+    }
+
+    #[test]
+    fn alamanc_from_str_reports_bad_range() {
+        let input = EXAMPLE.replace("39 0 15", "39 0");
+        let error = Alamanc::from_str(&input).unwrap_err();
 
-        assert_eq!(res, 35);
+        assert_eq!((error.line, error.column), (10, 5));
+        assert_eq!(error.expected, "a number");
     }
 
     #[test]
-    fn part_2_works() {
-        let seeds = "79 14 55 13";
+    fn alamanc_from_str_reads_any_number_of_stages() {
+        let input = EXAMPLE.split("\n\nhumidity").next().unwrap();
+        let alamanc = Alamanc::from_str(input).unwrap();
 
-        let seed_to_soil = "50 98 2
-52 50 48";
+        assert_eq!(alamanc.stages.len(), 6);
+        assert_eq!(alamanc.categories().last(), Some("humidity"));
 
-        let soil_to_fertilizer = "0 15 37
-37 52 2
-39 0 15";
+        let error = Alamanc::from_str("seeds: 1 2").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 11));
+    }
 
-        let fertilizer_to_water = "49 53 8
-0 11 42
-42 0 7
-57 7 4";
+    #[test]
+    fn alamanc_from_str_reports_broken_chains() {
+        let input = EXAMPLE.replace("soil-to-fertilizer", "dirt-to-fertilizer");
+        let error = Alamanc::from_str(&input).unwrap_err();
 
-        let water_to_light = "88 18 7
-18 25 70";
+        assert_eq!((error.line, error.column), (7, 1));
+        assert_eq!(error.expected, "\"soil\"");
 
-        let light_to_temperature = "45 77 23
-81 45 19
-68 64 13";
+        let input = EXAMPLE.replace("humidity-to-location", "humidity-to-soil");
+        let error = Alamanc::from_str(&input).unwrap_err();
 
-        let temperature_to_humidity = "0 69 1
-1 0 69";
+        assert_eq!((error.line, error.column), (31, 13));
+        assert_eq!(error.expected, "a category that is not in the chain yet");
 
-        let humidity_to_location = "60 56 37
-56 93 4";
+        let input = EXAMPLE.replace("seed-to-soil map:", "seed to soil map:");
+        let error = Alamanc::from_str(&input).unwrap_err();
+
+        assert_eq!(error.line, 3);
+        assert_eq!(error.expected, "\"<source>-to-<destination> map:\"");
+    }
+
+    #[test]
+    fn alamanc_convert_works() {
+        let alamanc = Alamanc::from_str(EXAMPLE).unwrap();
+
+        assert_eq!(alamanc.convert("seed", "humidity", 79), Some(78));
+        assert_eq!(alamanc.convert("soil", "light", 81), Some(74));
+        assert_eq!(alamanc.convert("seed", "seed", 5), Some(5));
+        assert_eq!(alamanc.convert("humidity", "seed", 78), None);
+        assert_eq!(alamanc.convert("seed", "dirt", 79), None);
+        assert_eq!(
+            alamanc.convert_interval("seed", "soil", 45..55).unwrap(),
+            [45..50, 52..57]
+        );
+    }
 
-        let res = part_2(
-            seeds,
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location,
+    #[test]
+    fn part_1_works() {
+        assert_eq!(Alamanc::from_str(EXAMPLE).unwrap().get_min_loc(), Some(35));
+    }
+
+    #[test]
+    fn part_2_works() {
+        assert_eq!(
+            Alamanc::from_str(EXAMPLE).unwrap().get_min_loc2().unwrap(),
+            46
         );
+    }
+
+    #[test]
+    fn part_2_needs_seed_pairs() {
+        let input = EXAMPLE.replace("seeds: 79 14 55 13", "seeds: 79 14 55");
+
+        assert!(Alamanc::from_str(&input).unwrap().get_min_loc2().is_err());
+    }
+
+    // ranges with disjoint sources below 1000, built from (gap before, length, destination)
+    fn disjoint_ranges() -> impl Strategy<Value = MapRanges> {
+        prop::collection::vec((0..20u64, 1..30u64, 0..1000u64), 0..10)
+            .prop_map(|specs| {
+                let mut start = 0;
+
+                specs
+                    .into_iter()
+                    .map(|(gap, len, dest_start)| {
+                        let range = MapRange {
+                            source_start: start + gap,
+                            dest_start,
+                            len,
+                        };
+                        start += gap + len;
+                        range
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .prop_shuffle()
+            .prop_map(MapRanges)
+    }
+
+    // ranges anywhere below 1000, overlapping or not
+    fn any_ranges() -> impl Strategy<Value = MapRanges> {
+        prop::collection::vec((0..500u64, 0..500u64, 1..50u64), 0..8).prop_map(|specs| {
+            MapRanges(
+                specs
+                    .into_iter()
+                    .map(|(source_start, dest_start, len)| MapRange {
+                        source_start,
+                        dest_start,
+                        len,
+                    })
+                    .collect(),
+            )
+        })
+    }
 
-        assert_eq!(res, 46);
+    proptest! {
+        #[test]
+        fn map_ranges_compose_agrees_with_get_mapping(first in any_ranges(), then in any_ranges()) {
+            let composed = first.compose(&then);
+            let table = Table::new(&composed);
+
+            for source in 0..1100 {
+                let expected = then.get_mapping(first.get_mapping(source));
+                prop_assert_eq!(composed.get_mapping(source), expected);
+                prop_assert_eq!(table.get_mapping(source), expected);
+            }
+        }
+
+        #[test]
+        fn map_ranges_get_sources_agrees_with_get_mapping(
+            ranges in any_ranges(),
+            start in 0..1000u64,
+            len in 0..100u64,
+        ) {
+            let dest = start..start + len;
+            let sources = ranges.get_sources(dest.clone());
+
+            for source in 0..1100 {
+                prop_assert_eq!(
+                    sources.iter().any(|sources| sources.contains(&source)),
+                    dest.contains(&ranges.get_mapping(source)),
+                    "source {}", source
+                );
+            }
+        }
+
+        #[test]
+        fn map_ranges_get_mapping_agrees_with_table(ranges in disjoint_ranges()) {
+            let mut table = HashMap::new();
+            for range in &ranges.0 {
+                for offset in 0..range.len {
+                    table.insert(range.source_start + offset, range.dest_start + offset);
+                }
+            }
+
+            for source in 0..600 {
+                let expected = table.get(&source).copied().unwrap_or(source);
+                prop_assert_eq!(ranges.get_mapping(source), expected);
+            }
+        }
+
+        #[test]
+        fn map_ranges_map_interval_agrees_with_get_mapping(
+            ranges in disjoint_ranges(),
+            start in 0..600u64,
+            len in 0..200u64,
+        ) {
+            let mapped = ranges.map_interval(start..start + len);
+            let mut numbers = mapped.into_iter().flatten().collect::<Vec<_>>();
+            numbers.sort_unstable();
+
+            let mut expected = (start..start + len)
+                .map(|source| ranges.get_mapping(source))
+                .collect::<Vec<_>>();
+            expected.sort_unstable();
+
+            prop_assert_eq!(numbers, expected);
+        }
     }
 }
@@ -1,30 +1,112 @@
-pub fn solve() {
-    let s1 = get_number_of_ways(59., 597.);
-    let s2 = get_number_of_ways(79., 1234.);
-    let s3 = get_number_of_ways(65., 1032.);
-    let s4 = get_number_of_ways(75., 1328.);
+use std::fmt::Display;
 
-    dbg!(s1 * s2 * s3 * s4);
+use tracing::trace;
 
-    let p2 = get_number_of_ways(59796575., 597123410321328.);
-    dbg!(p2);
+use crate::{
+    error::{parse_number, ParseError, SolveError},
+    solution::Solution,
+};
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Parsed = Vec<Race>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(races: &Self::Parsed) -> Result<impl Display, SolveError> {
+        part_1(races).ok_or(SolveError::Overflow)
+    }
+
+    fn part2(races: &Self::Parsed) -> Result<impl Display, SolveError> {
+        part_2(races)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Race {
+    pub time: u64,
+    pub best_dist: u64,
 }
 
-fn get_number_of_ways(time: f64, best_dist: f64) -> u32 {
+pub fn parse(input: &str) -> Result<Vec<Race>, ParseError> {
+    let mut lines = input.lines();
+    let times_line = lines.next().unwrap_or_default();
+    let distances_line = lines.next().unwrap_or_default();
+
+    let times = parse_row(times_line, "Time:")?;
+    let distances = parse_row(distances_line, "Distance:").map_err(|e| e.lines_down(1))?;
+
+    if times.len() != distances.len() {
+        let end = &distances_line[distances_line.len()..];
+        let expected = format!("{} distances", times.len());
+
+        return Err(ParseError::new(distances_line, end, expected).lines_down(1));
+    }
+
+    Ok(times
+        .into_iter()
+        .zip(distances)
+        .map(|(time, best_dist)| Race { time, best_dist })
+        .collect())
+}
+
+fn parse_row(line: &str, label: &str) -> Result<Vec<u64>, ParseError> {
+    let numbers = line
+        .strip_prefix(label)
+        .ok_or_else(|| ParseError::new(line, line, format!("\"{label}\"")))?;
+
+    numbers
+        .split_whitespace()
+        .map(|n| parse_number(line, n))
+        .collect()
+}
+
+/// `None` when the product overflows.
+pub fn part_1(races: &[Race]) -> Option<u64> {
+    races.iter().try_fold(1u64, |product, race| {
+        product.checked_mul(get_number_of_ways(race.time as f64, race.best_dist as f64))
+    })
+}
+
+// the spaces between the numbers are bad kerning, all races are one long race
+pub fn part_2(races: &[Race]) -> Result<u64, SolveError> {
+    if races.is_empty() {
+        return Err(SolveError::NoAnswer("there are no races".into()));
+    }
+
+    let time = races.iter().map(|r| r.time.to_string()).collect::<String>();
+    let best_dist = races
+        .iter()
+        .map(|r| r.best_dist.to_string())
+        .collect::<String>();
+
+    let joined = |digits: String| digits.parse::<u64>().map_err(|_| SolveError::Overflow);
+
+    Ok(get_number_of_ways(
+        joined(time)? as f64,
+        joined(best_dist)? as f64,
+    ))
+}
+
+pub fn get_number_of_ways(time: f64, best_dist: f64) -> u64 {
     // (time - t) * t = best_dist
     // - t^2 + time * t  - best_dist = 0
     // t1 = (-time + sqrt(time ^2 - 4 * -1 * -best_dist))/-2
     // t2 = (-time - sqrt(time ^2 - 4 * -1 * -best_dist))/-2
 
-    let t1 = (-1. * time + (time * time - 4. * best_dist).sqrt()) / -2.;
-    let t2 = (-1. * time - (time * time - 4. * best_dist).sqrt()) / -2.;
+    let t1 = (-time + (time * time - 4. * best_dist).sqrt()) / -2.;
+    let t2 = (-time - (time * time - 4. * best_dist).sqrt()) / -2.;
 
     let t1 = if t1 == t1.floor() { t1 + 1. } else { t1 };
     let t2 = if t2 == t2.floor() { t2 - 1. } else { t2 };
 
-    dbg!(t1, t2);
+    trace!(t1, t2, "race roots");
 
-    (t2.floor() - t1.ceil() + 1.) as u32
+    // the float to int cast saturates, no roots give NaN and count as 0
+    (t2.floor() - t1.ceil() + 1.) as u64
 }
 
 #[cfg(test)]
@@ -37,4 +119,25 @@ mod tests {
         assert_eq!(get_number_of_ways(15., 40.), 8);
         assert_eq!(get_number_of_ways(30., 200.), 9);
     }
+
+    #[test]
+    fn parts_work() {
+        let input = "Time:      7  15   30
+Distance:  9  40  200";
+
+        let races = parse(input).unwrap();
+
+        assert_eq!(part_1(&races), Some(288));
+        assert_eq!(part_2(&races).unwrap(), 71503);
+    }
+
+    #[test]
+    fn parse_reports_missing_distance() {
+        let input = "Time:      7  15   30
+Distance:  9  40";
+        let error = parse(input).unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 17));
+        assert_eq!(error.expected, "3 distances");
+    }
 }
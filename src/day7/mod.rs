@@ -1,108 +1,125 @@
-use std::{char, cmp::Ordering, collections::HashMap};
+use std::{char, cmp::Ordering, collections::HashMap, fmt::Display, str::FromStr};
 
-pub fn solve() {
-    let input = include_str!("./input.txt");
-    // let res = get_score(input);
+use tracing::trace;
 
-    // dbg!(res);
+use crate::{
+    error::{parse_lines, parse_number, ParseError, SolveError},
+    solution::Solution,
+};
 
-    let res = get_score_jokers(input);
+pub struct Day7;
 
-    dbg!(res);
-}
+impl Solution for Day7 {
+    // the hands are read differently by each part
+    type Parsed = (Vec<(Hand, u32)>, Vec<(HandJoker, u32)>);
 
-fn parse(input: &str) -> Vec<(Hand, u32)> {
-    input
-        .lines()
-        .map(|l| {
-            let parts = l.split(" ").collect::<Vec<_>>();
-            let bid = parts[1].parse::<u32>().unwrap();
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok((parse(input)?, parse_jokers(input)?))
+    }
 
-            (Hand::from_str(parts[0]), bid)
-        })
-        .collect()
+    fn part1((hands, _): &Self::Parsed) -> Result<impl Display, SolveError> {
+        get_score(hands).ok_or(SolveError::Overflow)
+    }
+
+    fn part2((_, hands): &Self::Parsed) -> Result<impl Display, SolveError> {
+        get_score_jokers(hands).ok_or(SolveError::Overflow)
+    }
 }
 
-fn parse_jokers(input: &str) -> Vec<(HandJoker, u32)> {
-    input
-        .lines()
-        .map(|l| {
-            let parts = l.split(" ").collect::<Vec<_>>();
-            let bid = parts[1].parse::<u32>().unwrap();
+pub fn parse(input: &str) -> Result<Vec<(Hand, u32)>, ParseError> {
+    parse_lines(input, parse_hand_and_bid)
+}
 
-            (HandJoker::from_str(parts[0]), bid)
-        })
-        .collect()
+pub fn parse_jokers(input: &str) -> Result<Vec<(HandJoker, u32)>, ParseError> {
+    parse_lines(input, parse_hand_and_bid)
 }
 
-fn get_score(input: &str) -> u32 {
-    let mut hands_and_bids = parse(input);
+fn parse_hand_and_bid<H>(line: &str) -> Result<(H, u32), ParseError>
+where
+    H: FromStr<Err = ParseError>,
+{
+    let (hand, bid) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new(line, &line[line.len()..], "a bid"))?;
+    let bid = parse_number(line, bid)?;
+
+    Ok((
+        hand.parse().map_err(|e: ParseError| e.within(line, hand))?,
+        bid,
+    ))
+}
+
+/// `None` when the score overflows.
+pub fn get_score(hands_and_bids: &[(Hand, u32)]) -> Option<u64> {
+    let mut hands_and_bids = hands_and_bids.iter().collect::<Vec<_>>();
 
     hands_and_bids.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
-    hands_and_bids
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (i, (hand, bid))| acc + bid * (i + 1) as u32)
+    total_winnings(hands_and_bids.iter().map(|(_, bid)| *bid))
 }
 
-fn get_score_jokers(input: &str) -> u32 {
-    let mut hands_and_bids = parse_jokers(input);
+/// `None` when the score overflows.
+pub fn get_score_jokers(hands_and_bids: &[(HandJoker, u32)]) -> Option<u64> {
+    let mut hands_and_bids = hands_and_bids.iter().collect::<Vec<_>>();
 
     hands_and_bids.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-    dbg!(&hands_and_bids);
+    trace!(?hands_and_bids, "ranked hands with jokers");
+
+    total_winnings(hands_and_bids.iter().map(|(_, bid)| *bid))
+}
 
-    hands_and_bids
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (i, (_, bid))| acc + bid * (i + 1) as u32)
+// every bid times its rank, the bids ordered from the weakest hand up
+fn total_winnings(bids: impl Iterator<Item = u32>) -> Option<u64> {
+    bids.zip(1u64..).try_fold(0u64, |acc, (bid, rank)| {
+        acc.checked_add(u64::from(bid).checked_mul(rank)?)
+    })
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Eq, Hash, Clone, Ord)]
-struct Card(u32);
+pub struct Card(pub u32);
 
 impl Card {
-    fn from_char(c: char) -> Self {
+    pub fn from_char(c: char) -> Option<Self> {
         let value = match c {
             'T' => 10,
             'J' => 11,
             'Q' => 12,
             'K' => 13,
             'A' => 14,
-            _ => c.to_digit(10).unwrap(),
+            _ => c.to_digit(10)?,
         };
 
-        Card(value)
+        Some(Card(value))
     }
 }
 
 #[derive(Debug, PartialEq)]
-struct Hand(Vec<Card>);
+pub struct Hand(pub Vec<Card>);
 
-impl Hand {
-    fn from_str(input: &str) -> Self {
-        let cards = input.chars().map(Card::from_char).collect::<Vec<_>>();
+impl FromStr for Hand {
+    type Err = ParseError;
 
-        Hand(cards)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Hand(parse_cards(input, Card::from_char)?))
     }
+}
 
+impl Hand {
     fn to_sets(&self) -> HashMap<Card, u32> {
         self.0.iter().fold(HashMap::new(), |mut acc, cur| {
-            acc.entry(cur.clone())
-                .and_modify(|c| *c = *c + 1)
-                .or_insert(1);
+            acc.entry(cur.clone()).and_modify(|c| *c += 1).or_insert(1);
 
             acc
         })
     }
 
-    fn rank(&self) -> u32 {
+    pub fn rank(&self) -> u32 {
         let sets = self.to_sets();
 
         let mut sorted_sets = sets.iter().collect::<Vec<_>>();
-        sorted_sets.sort_by(|a, b| b.1.cmp(&a.1));
+        sorted_sets.sort_by(|a, b| b.1.cmp(a.1));
 
-        match (sorted_sets.get(0), sorted_sets.get(1), sorted_sets.get(2)) {
+        match (sorted_sets.first(), sorted_sets.get(1), sorted_sets.get(2)) {
             (Some((_, 5)), None, None) => 0,
             (Some((_, 4)), Some((_, 1)), None) => 1,
             (Some((_, 3)), Some((_, 2)), None) => 2,
@@ -128,47 +145,47 @@ impl PartialOrd for Hand {
 }
 
 #[derive(Debug, PartialEq)]
-struct HandJoker(Vec<CardJoker>);
+pub struct HandJoker(pub Vec<CardJoker>);
 
-impl HandJoker {
-    fn from_str(input: &str) -> Self {
-        let cards = input.chars().map(CardJoker::from_char).collect::<Vec<_>>();
+impl FromStr for HandJoker {
+    type Err = ParseError;
 
-        Self(cards)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(Self(parse_cards(input, CardJoker::from_char)?))
     }
+}
 
+impl HandJoker {
     fn to_sets(&self) -> HashMap<CardJoker, u32> {
         let mut sets = self.0.iter().fold(HashMap::new(), |mut acc, cur| {
-            acc.entry(cur.clone())
-                .and_modify(|c| *c = *c + 1)
-                .or_insert(1);
+            acc.entry(cur.clone()).and_modify(|c| *c += 1).or_insert(1);
 
             acc
         });
 
         if let Some(jokers) = sets.get(&CardJoker(0)) {
             if *jokers < 5 {
-                let jokers = jokers.clone();
+                let jokers = *jokers;
                 sets.remove(&CardJoker(0));
 
                 let max_set = sets.iter_mut().max_by(|a, b| a.1.cmp(&b.1)).unwrap();
 
-                *max_set.1 = *max_set.1 + jokers;
+                *max_set.1 += jokers;
             }
         }
 
         sets
     }
 
-    fn rank(&self) -> u32 {
+    pub fn rank(&self) -> u32 {
         let sets = self.to_sets();
 
         let mut sorted_sets = sets.iter().collect::<Vec<_>>();
-        sorted_sets.sort_by(|a, b| b.1.cmp(&a.1));
+        sorted_sets.sort_by(|a, b| b.1.cmp(a.1));
 
-        // dbg!(&sorted_sets);
+        trace!(?sorted_sets, "joker hand sets");
 
-        match (sorted_sets.get(0), sorted_sets.get(1), sorted_sets.get(2)) {
+        match (sorted_sets.first(), sorted_sets.get(1), sorted_sets.get(2)) {
             (Some((_, 5)), None, None) => 0,
             (Some((_, 4)), Some((_, 1)), None) => 1,
             (Some((_, 3)), Some((_, 2)), None) => 2,
@@ -193,21 +210,48 @@ impl PartialOrd for HandJoker {
     }
 }
 
+const HAND_SIZE: usize = 5;
+
+fn parse_cards<C>(
+    input: &str,
+    from_char: impl Fn(char) -> Option<C>,
+) -> Result<Vec<C>, ParseError> {
+    let cards = input
+        .char_indices()
+        .map(|(i, c)| from_char(c).ok_or_else(|| ParseError::new(input, &input[i..], "a card")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if cards.len() != HAND_SIZE {
+        let at = input
+            .char_indices()
+            .nth(HAND_SIZE)
+            .map_or(input.len(), |(i, _)| i);
+
+        return Err(ParseError::new(
+            input,
+            &input[at..],
+            format!("{HAND_SIZE} cards"),
+        ));
+    }
+
+    Ok(cards)
+}
+
 #[derive(Debug, PartialEq, PartialOrd, Eq, Hash, Clone, Ord)]
-struct CardJoker(u32);
+pub struct CardJoker(pub u32);
 
 impl CardJoker {
-    fn from_char(c: char) -> Self {
+    pub fn from_char(c: char) -> Option<Self> {
         let value = match c {
             'T' => 10,
             'J' => 0,
             'Q' => 12,
             'K' => 13,
             'A' => 14,
-            _ => c.to_digit(10).unwrap(),
+            _ => c.to_digit(10)?,
         };
 
-        CardJoker(value)
+        Some(CardJoker(value))
     }
 }
 
@@ -221,22 +265,39 @@ mod tests {
 
         let expected = Hand(vec![Card(3), Card(2), Card(10), Card(3), Card(13)]);
 
-        assert_eq!(expected, Hand::from_str(input));
+        assert_eq!(expected, Hand::from_str(input).unwrap());
+    }
+
+    #[test]
+    fn parse_reports_bad_card() {
+        let error = parse("32T3K 765\nT5XJ5 684").unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.found(), Some("XJ5"));
+    }
+
+    #[test]
+    fn parse_reports_hand_size() {
+        let error = parse_jokers("JJ 765").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (3, "5 cards"));
+
+        let error = parse("32T3K2 765").unwrap_err();
+        assert_eq!(error.found(), Some("2"));
     }
 
     #[test]
     fn hand_cmp_works() {
-        let hand0 = Hand::from_str("54321");
-        let hand1 = Hand::from_str("64321");
-        let hand2 = Hand::from_str("32T3K");
-        let hand3 = Hand::from_str("KK677");
-        let hand4 = Hand::from_str("T55J5");
-        let hand5 = Hand::from_str("12112");
-        let hand6 = Hand::from_str("12111");
-        let hand7 = Hand::from_str("AAAAA");
-
-        let hand8 = Hand::from_str("KTJJT");
-        let hand9 = Hand::from_str("KK677");
+        let hand0 = Hand::from_str("54321").unwrap();
+        let hand1 = Hand::from_str("64321").unwrap();
+        let hand2 = Hand::from_str("32T3K").unwrap();
+        let hand3 = Hand::from_str("KK677").unwrap();
+        let hand4 = Hand::from_str("T55J5").unwrap();
+        let hand5 = Hand::from_str("12112").unwrap();
+        let hand6 = Hand::from_str("12111").unwrap();
+        let hand7 = Hand::from_str("AAAAA").unwrap();
+
+        let hand8 = Hand::from_str("KTJJT").unwrap();
+        let hand9 = Hand::from_str("KK677").unwrap();
 
         assert!(hand0 < hand1, "high card wins");
         assert!(hand1 < hand2, "pair wins");
@@ -250,11 +311,11 @@ mod tests {
 
     #[test]
     fn hand_joker_cmp_works() {
-        assert!(HandJoker::from_str("AAT12") < HandJoker::from_str("AAJ12"));
-        assert!(HandJoker::from_str("AJ523") < HandJoker::from_str("A5523"));
-        assert!(HandJoker::from_str("11112") < HandJoker::from_str("JJJJJ"));
-        assert!(HandJoker::from_str("AAA22") < HandJoker::from_str("AJJ2A"));
-        assert!(HandJoker::from_str("TKAKT") < HandJoker::from_str("TQ77J"));
+        assert!(HandJoker::from_str("AAT12").unwrap() < HandJoker::from_str("AAJ12").unwrap());
+        assert!(HandJoker::from_str("AJ523").unwrap() < HandJoker::from_str("A5523").unwrap());
+        assert!(HandJoker::from_str("11112").unwrap() < HandJoker::from_str("JJJJJ").unwrap());
+        assert!(HandJoker::from_str("AAA22").unwrap() < HandJoker::from_str("AJJ2A").unwrap());
+        assert!(HandJoker::from_str("TKAKT").unwrap() < HandJoker::from_str("TQ77J").unwrap());
     }
 
     #[test]
@@ -265,9 +326,9 @@ KK677 28
 KTJJT 220
 QQQJA 483";
 
-        let expected = get_score(input);
+        let expected = get_score(&parse(input).unwrap());
 
-        assert_eq!(expected, 6440);
+        assert_eq!(expected, Some(6440));
     }
 
     #[test]
@@ -278,8 +339,8 @@ KK677 28
 KTJJT 220
 QQQJA 483";
 
-        let expected = get_score_jokers(input);
+        let expected = get_score_jokers(&parse_jokers(input).unwrap());
 
-        assert_eq!(expected, 5905);
+        assert_eq!(expected, Some(5905));
     }
 }
@@ -1,96 +1,160 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display, iter, str::FromStr};
 
 use regex::Regex;
 
-pub fn solve() {
-    let input = include_str!("./input.txt");
+use crate::{
+    cycle::{self, Cycle},
+    error::{parse_lines, ParseError, SolveError},
+    solution::Solution,
+};
 
-    let sim = Simulation::from_str(input);
+pub struct Day8;
 
-    dbg!(sim.run());
-    dbg!(sim.run_part2());
+impl Solution for Day8 {
+    type Parsed = Simulation;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        input.parse()
+    }
+
+    fn part1(sim: &Self::Parsed) -> Result<impl Display, SolveError> {
+        sim.run()
+    }
+
+    fn part2(sim: &Self::Parsed) -> Result<impl Display, SolveError> {
+        sim.run_part2()
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
 
 impl Direction {
-    fn from_char(c: char) -> Self {
+    pub fn from_char(c: char) -> Option<Self> {
         match c {
-            'L' => Self::Left,
-            'R' => Self::Right,
-            _ => panic!("no such dir"),
+            'L' => Some(Self::Left),
+            'R' => Some(Self::Right),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug, PartialEq)]
-struct Node {
-    text: String,
-    left: String,
-    right: String,
+pub struct Node {
+    pub text: String,
+    pub left: String,
+    pub right: String,
 }
 
 #[derive(Debug, PartialEq)]
-struct Simulation {
-    graph: HashMap<String, Node>,
-    instructions: Vec<Direction>,
+pub struct Simulation {
+    pub graph: HashMap<String, Node>,
+    pub instructions: Vec<Direction>,
 }
 
-impl Simulation {
-    fn from_str(input: &str) -> Simulation {
-        let mut lines = input.split("\n");
-        let directions_str = lines.next().unwrap();
-        let instructions = directions_str
-            .chars()
-            .map(Direction::from_char)
-            .collect::<Vec<_>>();
+impl FromStr for Simulation {
+    type Err = ParseError;
 
-        lines.next().unwrap();
-
-        let line_regex = Regex::new(r"([0-9A-Z]+) = \(([0-9A-Z]+), ([0-9A-Z]+)\)").unwrap();
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut lines = input.splitn(3, '\n');
+        let directions_str = lines.next().unwrap_or_default();
+        let instructions = directions_str
+            .char_indices()
+            .map(|(i, c)| {
+                Direction::from_char(c).ok_or_else(|| {
+                    ParseError::new(directions_str, &directions_str[i..], "\"L\" or \"R\"")
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if instructions.is_empty() {
+            return Err(ParseError::new(
+                directions_str,
+                directions_str,
+                "\"L\" or \"R\"",
+            ));
+        }
 
-        let graph = lines
-            .map(|l| {
-                let parts = line_regex.captures(l).unwrap();
-                let current = parts.get(1).unwrap().as_str();
-                let left = parts.get(2).unwrap().as_str();
-                let right = parts.get(3).unwrap().as_str();
+        let blank = lines.next().unwrap_or_default();
+        if !blank.is_empty() {
+            return Err(ParseError::new(blank, blank, "an empty line").lines_down(1));
+        }
 
-                (
-                    current.to_string(),
-                    Node {
-                        text: current.into(),
-                        left: left.into(),
-                        right: right.into(),
-                    },
-                )
-            })
-            .collect();
+        let line_regex = Regex::new(r"^([0-9A-Z]+) = \(([0-9A-Z]+), ([0-9A-Z]+)\)$").unwrap();
+
+        let node_lines = lines.next().unwrap_or_default();
+        let nodes = parse_lines(node_lines, |l| {
+            let parts = line_regex
+                .captures(l)
+                .ok_or_else(|| ParseError::new(l, l, "\"<node> = (<left>, <right>)\""))?;
+            let current = parts.get(1).unwrap().as_str();
+            let left = parts.get(2).unwrap().as_str();
+            let right = parts.get(3).unwrap().as_str();
+
+            Ok((
+                current.to_string(),
+                Node {
+                    text: current.into(),
+                    left: left.into(),
+                    right: right.into(),
+                },
+            ))
+        })
+        .map_err(|e| e.lines_down(2))?;
+
+        let graph: HashMap<_, _> = nodes.into_iter().collect();
+
+        // every left and right has to be a node of its own
+        for (i, l) in node_lines.split('\n').enumerate() {
+            let Some(parts) = line_regex.captures(l) else {
+                continue;
+            };
+
+            for next in [parts.get(2), parts.get(3)].into_iter().flatten() {
+                if !graph.contains_key(next.as_str()) {
+                    return Err(ParseError::new(l, next.as_str(), "a known node").lines_down(i + 2));
+                }
+            }
+        }
 
-        Simulation {
+        Ok(Simulation {
             graph,
             instructions,
-        }
+        })
     }
+}
 
-    fn run(&self) -> u64 {
-        let mut cur = self.graph.get("AAA".into()).unwrap();
+impl Simulation {
+    pub fn run(&self) -> Result<u64, SolveError> {
+        let mut cur = self
+            .graph
+            .get("AAA")
+            .ok_or_else(|| SolveError::NoAnswer("there is no node AAA".into()))?;
         let mut instruction_i = 0;
 
-        while cur.text != "ZZZ".to_string() {
+        while cur.text != "ZZZ" {
+            if instruction_i == self.max_steps() {
+                return Err(SolveError::NoAnswer("ZZZ can't be reached from AAA".into()));
+            }
+
             cur = self.get_next(instruction_i, cur);
 
             instruction_i += 1;
         }
 
-        instruction_i as u64
+        Ok(instruction_i as u64)
+    }
+
+    // once every node was visited at every instruction the walk goes around in circles
+    fn max_steps(&self) -> usize {
+        self.graph.len() * self.instructions.len()
     }
 
-    fn get_next(&self, instruction_i: usize, from_node: &Node) -> &Node {
+    /// The node reached from `from_node` by the instruction at step `instruction_i`.
+    pub fn get_next(&self, instruction_i: usize, from_node: &Node) -> &Node {
         let d = self
             .instructions
             .get(instruction_i % self.instructions.len())
@@ -104,21 +168,7 @@ impl Simulation {
         cur
     }
 
-    // returns (number of iterations until reached z, z node)
-    fn run_from(&self, instruction_i: usize, from: &str) -> (usize, &Node) {
-        let mut cur = self.graph.get(from).unwrap();
-        let mut delta = 0;
-
-        while !cur.text.ends_with("Z") {
-            cur = self.get_next(instruction_i + delta, &cur);
-
-            delta += 1;
-        }
-
-        (delta, cur)
-    }
-
-    fn run_part2(&self) -> u64 {
+    pub fn run_part2(&self) -> Result<u64, SolveError> {
         let start_nodes = self
             .graph
             .iter()
@@ -126,34 +176,150 @@ impl Simulation {
             .map(|(_, v)| v)
             .collect::<Vec<_>>();
 
-        // for each start node find number of steps to reach end node, and circle length from z_node to itelf
-        // there must be a circle since we have limited nodes in the graph and everything connects to something
-        let data = start_nodes
+        if start_nodes.is_empty() {
+            return Err(SolveError::NoAnswer(
+                "there are no nodes ending in A".into(),
+            ));
+        }
+
+        // a walk repeats itself once it is back on a node at the same instruction
+        let len = self.instructions.len();
+        let step = |&(node, i): &(&Node, usize)| (self.get_next(i, node), (i + 1) % len);
+
+        let mut walks = Vec::with_capacity(start_nodes.len());
+        for node in start_nodes {
+            let cycle = cycle::find((node, 0), step);
+            let z_steps = iter::successors(Some((node, 0)), |state| Some(step(state)))
+                .take(cycle.prefix + cycle.len)
+                .enumerate()
+                .filter(|(_, (node, _))| node.text.ends_with('Z'))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+
+            if z_steps.is_empty() {
+                return Err(SolveError::NoAnswer(format!(
+                    "no node ending in Z can be reached from {}",
+                    node.text
+                )));
+            }
+
+            walks.push(Walk { cycle, z_steps });
+        }
+
+        // until every walk is in its cycle the steps are tried one by one
+        let prefix = walks
             .iter()
-            .map(|c| {
-                let (n_steps, z_node) = self.run_from(0, &c.text);
-                let next = self.get_next(n_steps, &z_node);
-                let (n_circle, _) = self.run_from(n_steps + 1, &next.text);
+            .map(|walk| walk.cycle.prefix)
+            .max()
+            .unwrap_or(0);
+        if let Some(i) = (0..prefix).find(|&i| walks.iter().all(|walk| walk.is_on_z(i))) {
+            return Ok(i as u64);
+        }
 
-                (n_steps, n_circle + 1)
+        // after that a walk is on a z node every `len` steps from each z step of its cycle, the
+        // answer is the first step past the prefix where they all agree
+        let mut systems = vec![(0, 1)];
+        for walk in &walks {
+            let circle = walk.cycle.len as u128;
+            let mut combined = vec![];
+
+            for &system in &systems {
+                for &z_step in walk.z_steps.iter().filter(|&&i| i >= walk.cycle.prefix) {
+                    combined.extend(combine(system, (z_step as u128 % circle, circle))?);
+                }
+            }
+
+            combined.sort_unstable();
+            combined.dedup();
+            if combined.len() > MAX_SYSTEMS {
+                return Err(SolveError::NoAnswer(
+                    "the nodes ending in Z line up in too many ways".into(),
+                ));
+            }
+            systems = combined;
+        }
+
+        let prefix = prefix as u128;
+        let instruction_i = systems
+            .iter()
+            .map(|&(rest, circle)| {
+                if rest >= prefix {
+                    rest
+                } else {
+                    rest + (prefix - rest).div_ceil(circle) * circle
+                }
             })
-            .collect::<Vec<_>>();
+            .min()
+            .ok_or_else(|| {
+                SolveError::NoAnswer(
+                    "the nodes ending in Z are never reached at the same time".into(),
+                )
+            })?;
+
+        u64::try_from(instruction_i).map_err(|_| SolveError::Overflow)
+    }
+}
 
-        let max_n_steps = data.iter().max_by(|a, b| a.1.cmp(&b.1)).unwrap();
+// how many ways of lining up the z nodes of the walks are followed before giving up
+const MAX_SYSTEMS: usize = 10_000;
 
-        let max_steps = max_n_steps.0;
-        let circle_factor = max_n_steps.1;
+// the steps of one start node's walk that end on a z node, up to the end of its first cycle
+struct Walk {
+    cycle: Cycle,
+    z_steps: Vec<usize>,
+}
 
-        let mut instruction_i = max_steps;
+impl Walk {
+    fn is_on_z(&self, i: usize) -> bool {
+        self.z_steps.binary_search(&self.cycle.index(i)).is_ok()
+    }
+}
 
-        // start from the start node that has max steps to z. increase the index by it's cirlcle length
-        // until we find a number that matches all other start nodes
-        while !data.iter().all(|d| (instruction_i - d.0) % d.1 == 0) {
-            instruction_i += circle_factor;
-        }
+// combines `x % m1 == r1` and `x % m2 == r2` into `x % m == r`, with `r < m`, `None` when no
+// `x` satisfies both
+fn combine(
+    (r1, m1): (u128, u128),
+    (r2, m2): (u128, u128),
+) -> Result<Option<(u128, u128)>, SolveError> {
+    let g = gcd(m1, m2);
+    let diff = (r2 + m2 - r1 % m2) % m2;
+
+    if diff % g != 0 {
+        return Ok(None);
+    }
 
-        instruction_i as u64
+    // the answer has to fit in a u64, which keeps every product below fitting in a u128
+    let m = m1 / g * m2;
+    if m > u64::MAX.into() {
+        return Err(SolveError::Overflow);
     }
+
+    let n = m2 / g;
+    let k = (diff / g) % n * inverse(m1 / g % n, n) % n;
+
+    Ok(Some(((r1 + m1 * k) % m, m)))
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// `a` times the result is 1 modulo `n`, for `a` and `n` without common factors
+fn inverse(a: u128, n: u128) -> u128 {
+    let (mut old_r, mut r) = (a as i128, n as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+
+    old_s.rem_euclid(n as i128) as u128
 }
 
 #[cfg(test)]
@@ -198,7 +364,19 @@ ZZZ = (ZZZ, ZZZ)";
             ]),
         };
 
-        assert_eq!(Simulation::from_str(input), expected);
+        assert_eq!(Simulation::from_str(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn simulation_from_str_reports_bad_node() {
+        let input = "LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA ZZZ)";
+        let error = Simulation::from_str(input).unwrap_err();
+
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(error.expected, "\"<node> = (<left>, <right>)\"");
     }
 
     #[test]
@@ -209,9 +387,9 @@ AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
 
-        let sim = Simulation::from_str(input);
+        let sim = Simulation::from_str(input).unwrap();
 
-        assert_eq!(sim.run(), 6)
+        assert_eq!(sim.run().unwrap(), 6)
     }
 
     #[test]
@@ -226,8 +404,55 @@ ZZZ = (ZZZ, ZZZ)";
 22C = (22Z, 22Z)
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
-        let sim = Simulation::from_str(input);
+        let sim = Simulation::from_str(input).unwrap();
+
+        assert_eq!(sim.run_part2().unwrap(), 6)
+    }
+
+    #[test]
+    fn simulation_run2_handles_several_z_nodes_in_a_cycle() {
+        // the first walk is on a z node after 1 or 2 steps in every 4, the second after 3 in 5
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (12Z, 12Z)
+12Z = (11C, 11C)
+11C = (11A, 11A)
+22A = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22D, 22D)
+22D = (22A, 22A)";
+        let sim = Simulation::from_str(input).unwrap();
+
+        assert_eq!(sim.run_part2().unwrap(), 13)
+    }
 
-        assert_eq!(sim.run_part2(), 6)
+    #[test]
+    fn simulation_from_str_reports_unknown_node() {
+        let error = Simulation::from_str("L\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 8));
+        assert_eq!(error.expected, "a known node");
+
+        let error = Simulation::from_str("\n\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(error.line, 1);
+    }
+
+    #[test]
+    fn simulation_reports_unreachable_nodes() {
+        let sim = Simulation::from_str("L\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)").unwrap();
+        assert!(sim.run().is_err());
+
+        // the first start is on a z node after odd counts, the second after even ones
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)";
+        let sim = Simulation::from_str(input).unwrap();
+        assert!(sim.run_part2().is_err());
     }
 }
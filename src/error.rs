@@ -0,0 +1,179 @@
+use std::{any::Any, error::Error, fmt, str::FromStr};
+
+/// A malformed puzzle input, pointing at the line and column where parsing stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1 based line of the input
+    pub line: usize,
+    /// 1 based column (in chars) of the line
+    pub column: usize,
+    /// What the parser was looking for at that position
+    pub expected: String,
+    /// The offending line
+    pub text: String,
+}
+
+impl ParseError {
+    /// An error at `token`, which must be a slice of `line`.
+    pub fn new(line: &str, token: &str, expected: impl Into<String>) -> Self {
+        ParseError {
+            line: 1,
+            column: column_of(line, token),
+            expected: expected.into(),
+            text: line.to_string(),
+        }
+    }
+
+    /// Re-anchors an error reported on `part`, a slice of `line`, to the whole line.
+    pub fn within(self, line: &str, part: &str) -> Self {
+        ParseError {
+            column: column_of(line, part) + self.column - 1,
+            text: line.to_string(),
+            ..self
+        }
+    }
+
+    /// Moves the error `lines` lines down, for errors reported relative to a block of the input.
+    pub fn lines_down(self, lines: usize) -> Self {
+        ParseError {
+            line: self.line + lines,
+            ..self
+        }
+    }
+
+    /// The text found at the error position, or `None` at the end of the line.
+    pub fn found(&self) -> Option<&str> {
+        self.text
+            .char_indices()
+            .nth(self.column - 1)
+            .map(|(i, _)| self.text[i..].split_whitespace().next().unwrap_or(" "))
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+
+        match self.found() {
+            Some(found) => write!(f, ", found \"{found}\"")?,
+            None => write!(f, ", found end of line")?,
+        }
+
+        write!(f, "\n  {}\n  {:>2$}", self.text, "^", self.column)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Why a day could not answer.
+#[derive(Debug)]
+pub enum SolveError {
+    Parse(ParseError),
+    /// The input parsed but has no answer, with the reason
+    NoAnswer(String),
+    /// The answer does not fit in the solver's number type
+    Overflow,
+    /// The solver panicked, with the panic message when it has one
+    Panic(Option<String>),
+}
+
+impl SolveError {
+    pub fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => Some(*message),
+            Err(payload) => payload.downcast_ref::<&str>().map(|m| m.to_string()),
+        };
+
+        SolveError::Panic(message)
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Parse(e) => write!(f, "could not parse input: {e}"),
+            SolveError::NoAnswer(reason) => write!(f, "no answer: {reason}"),
+            SolveError::Overflow => write!(f, "the answer overflows"),
+            SolveError::Panic(Some(message)) => write!(f, "solver panicked: {message}"),
+            SolveError::Panic(None) => write!(f, "solver panicked"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        SolveError::Parse(e)
+    }
+}
+
+/// Parses every line of `input` with `parse`, numbering errors by the line they occur on.
+pub fn parse_lines<T>(
+    input: &str,
+    parse: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|e| e.lines_down(i)))
+        .collect()
+}
+
+/// Parses `token`, a slice of `line`, as a number.
+pub fn parse_number<T: FromStr>(line: &str, token: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::new(line, token, "a number"))
+}
+
+fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize)
+        .checked_sub(line.as_ptr() as usize)
+        .filter(|offset| offset + token.len() <= line.len())
+        .expect("token is not a slice of the line");
+
+    line[..offset].chars().count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_points_at_token() {
+        let line = "Card 1: 41 x8 | 83";
+        let error = ParseError::new(line, &line[11..13], "a number");
+
+        assert_eq!(error.column, 12);
+        assert_eq!(error.found(), Some("x8"));
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 12: expected a number, found \"x8\"
+  Card 1: 41 x8 | 83
+             ^"
+        );
+    }
+
+    #[test]
+    fn parse_error_within_moves_column() {
+        let line = "Game 1: 3 blue; 4 purple";
+        let part = &line[15..];
+        let error = ParseError::new(part, &part[3..], "a color").within(line, part);
+
+        assert_eq!(error.column, 19);
+        assert_eq!(error.text, line);
+    }
+
+    #[test]
+    fn parse_lines_numbers_errors() {
+        let error = parse_lines("1\n2\nx", |l| parse_number::<u32>(l, l)).unwrap_err();
+
+        assert_eq!(error.line, 3);
+        assert_eq!(error.column, 1);
+    }
+}
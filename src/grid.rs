@@ -0,0 +1,411 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::error::ParseError;
+
+/// A position in a [`Grid`], `x` is the column and `y` the row counted from the top.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pos {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Pos {
+    pub fn new(x: usize, y: usize) -> Self {
+        Pos { x, y }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Dir {
+    Up,
+    Left,
+    Down,
+    Right,
+}
+
+impl Dir {
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Left, Dir::Down, Dir::Right];
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Dir::Up => Dir::Down,
+            Dir::Left => Dir::Right,
+            Dir::Down => Dir::Up,
+            Dir::Right => Dir::Left,
+        }
+    }
+}
+
+/// A cell that is read from and rendered as a single char.
+pub trait Cell: Sized {
+    /// What a cell looks like, for parse errors
+    const EXPECTED: &'static str;
+
+    fn from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+impl Cell for char {
+    const EXPECTED: &'static str = "a char";
+
+    fn from_char(c: char) -> Option<Self> {
+        Some(c)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+/// A rectangular grid, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// `None` when the rows are empty or not all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first()?.len();
+
+        if width == 0 || rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Reads a grid with a cell per char, ignoring whitespace around each line.
+    /// `expected` describes a cell in the errors about bad chars and short rows.
+    pub fn parse_with(
+        input: &str,
+        from_char: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+
+        for (y, line) in input.lines().enumerate() {
+            let row = line.trim();
+            if y == 0 {
+                width = row.chars().count();
+            }
+
+            let error = |at, expected| ParseError::new(line, at, expected).lines_down(y);
+            let mut row_width = 0;
+
+            for (i, c) in row.char_indices() {
+                if row_width == width {
+                    return Err(error(&row[i..], "end of line"));
+                }
+
+                cells.push(from_char(c).ok_or_else(|| error(&row[i..], expected))?);
+                row_width += 1;
+            }
+
+            if row_width < width || width == 0 {
+                return Err(error(&row[row.len()..], expected));
+            }
+
+            height += 1;
+        }
+
+        if height == 0 {
+            return Err(ParseError::new(input, input, expected));
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.y * self.width + pos.x])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.y * self.width + pos.x])
+    }
+
+    /// The position next to `pos` in `dir`, `None` past the edge.
+    pub fn step(&self, pos: Pos, dir: Dir) -> Option<Pos> {
+        let next = match dir {
+            Dir::Up => Pos::new(pos.x, pos.y.checked_sub(1)?),
+            Dir::Left => Pos::new(pos.x.checked_sub(1)?, pos.y),
+            Dir::Down => Pos::new(pos.x, pos.y + 1),
+            Dir::Right => Pos::new(pos.x + 1, pos.y),
+        };
+
+        self.contains(next).then_some(next)
+    }
+
+    /// Up, left, down and right of `pos`, as far as they are inside the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Dir::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// The eight positions around `pos`, diagonals included, as far as they are inside the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&offset| offset != (0, 0))
+            .filter_map(move |(dx, dy)| {
+                let x = pos.x.checked_add_signed(dx)?;
+                let y = pos.y.checked_add_signed(dy)?;
+
+                Some(Pos::new(x, y)).filter(|&next| self.contains(next))
+            })
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = Pos> {
+        let width = self.width;
+
+        (0..self.width * self.height).map(move |i| Pos::new(i % width, i / width))
+    }
+
+    /// The positions along the edge on the `dir` side, top to bottom or left to right.
+    pub fn edge(&self, dir: Dir) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        let len = match dir {
+            Dir::Up | Dir::Down => width,
+            Dir::Left | Dir::Right => height,
+        };
+
+        (0..len).map(move |i| match dir {
+            Dir::Up => Pos::new(i, 0),
+            Dir::Down => Pos::new(i, height - 1),
+            Dir::Left => Pos::new(0, i),
+            Dir::Right => Pos::new(width - 1, i),
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Mirrors the grid over its main diagonal, rows become columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rebuild(self.height, self.width, |pos| Pos::new(pos.y, pos.x))
+    }
+
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+
+        self.rebuild(self.height, self.width, |pos| {
+            Pos::new(pos.y, height - 1 - pos.x)
+        })
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+
+        self.rebuild(self.height, self.width, |pos| {
+            Pos::new(width - 1 - pos.y, pos.x)
+        })
+    }
+
+    // a `width` by `height` grid taking every cell from the position `source` gives for it
+    fn rebuild(&self, width: usize, height: usize, source: impl Fn(Pos) -> Pos) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..width * height)
+            .map(|i| self[source(Pos::new(i % width, i / width))].clone())
+            .collect();
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Draws the grid a char per cell, a line per row.
+    pub fn render(&self, cell: impl Fn(Pos, &T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+
+        for (pos, value) in self.iter() {
+            if pos.x == 0 && pos.y > 0 {
+                out.push('\n');
+            }
+            out.push(cell(pos, value));
+        }
+
+        out
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).expect("position is outside the grid")
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos).expect("position is outside the grid")
+    }
+}
+
+impl<T: Cell> FromStr for Grid<T> {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(input, T::from_char, T::EXPECTED)
+    }
+}
+
+impl<T: Cell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(|_, cell| cell.to_char()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        "abc\ndef".parse().unwrap()
+    }
+
+    #[test]
+    fn grid_from_str_works() {
+        let grid = grid();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(1, 1)], 'e');
+        assert_eq!(grid.get(Pos::new(3, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+        assert_eq!(
+            Grid::from_rows(vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']]),
+            Some(grid)
+        );
+    }
+
+    #[test]
+    fn grid_from_str_reports_ragged_rows() {
+        let error = "abc\nde".parse::<Grid<char>>().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = "abc\nabcd".parse::<Grid<char>>().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "end of line");
+
+        assert!("".parse::<Grid<char>>().is_err());
+        assert_eq!(Grid::from_rows(vec![vec![1], vec![]]), None);
+    }
+
+    #[test]
+    fn grid_step_stays_inside() {
+        let grid = grid();
+        let corner = Pos::new(0, 0);
+
+        assert_eq!(grid.step(corner, Dir::Up), None);
+        assert_eq!(grid.step(corner, Dir::Left), None);
+        assert_eq!(grid.step(corner, Dir::Down), Some(Pos::new(0, 1)));
+        assert_eq!(grid.step(Pos::new(2, 1), Dir::Right), None);
+    }
+
+    #[test]
+    fn grid_neighbours_work() {
+        let grid = grid();
+
+        assert_eq!(
+            grid.neighbours4(Pos::new(1, 0)).collect::<Vec<_>>(),
+            vec![Pos::new(0, 0), Pos::new(1, 1), Pos::new(2, 0)]
+        );
+        assert_eq!(grid.neighbours8(Pos::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours8(Pos::new(1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn grid_edge_works() {
+        let grid = grid();
+
+        assert_eq!(
+            grid.edge(Dir::Down).collect::<Vec<_>>(),
+            vec![Pos::new(0, 1), Pos::new(1, 1), Pos::new(2, 1)]
+        );
+        assert_eq!(
+            grid.edge(Dir::Right).collect::<Vec<_>>(),
+            vec![Pos::new(2, 0), Pos::new(2, 1)]
+        );
+    }
+
+    #[test]
+    fn grid_transpose_and_rotate_work() {
+        let grid = grid();
+
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Where the puzzle input of a day is read from.
+pub enum Source {
+    /// A single input file, `-` reads stdin
+    File(PathBuf),
+    /// A directory laid out like `src`, holding `dayN/input.txt` for every day
+    Dir(PathBuf),
+}
+
+impl Source {
+    pub fn path(&self, day: u8) -> PathBuf {
+        match self {
+            Source::File(path) => path.clone(),
+            Source::Dir(dir) => input_path(dir, day, "input"),
+        }
+    }
+
+    pub fn read(&self, day: u8) -> io::Result<String> {
+        let path = self.path(day);
+
+        if path == Path::new("-") {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+
+            return Ok(input);
+        }
+
+        fs::read_to_string(&path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+    }
+}
+
+/// Path of the input called `name` of `day` in an inputs directory.
+pub fn input_path(dir: &Path, day: u8, name: &str) -> PathBuf {
+    dir.join(format!("day{day}")).join(format!("{name}.txt"))
+}
+
+/// Turns CRLF and CR line endings into `\n`, and drops a leading byte order mark, whitespace at
+/// the end of lines and blank lines at the end of the input. Lines keep their numbers, so parse
+/// errors point at the same place in the original.
+pub fn normalize(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let input = input.replace("\r\n", "\n").replace('\r', "\n");
+
+    let lines = input.split('\n').map(str::trim_end).collect::<Vec<_>>();
+    let len = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(0, |last| last + 1);
+
+    lines[..len].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_works() {
+        assert_eq!(normalize("a\r\nb \r\n\r\n\n"), "a\nb");
+        assert_eq!(normalize("\u{feff}a\rb\n\nc\n"), "a\nb\n\nc");
+        assert_eq!(normalize(" \n\n"), "");
+    }
+}
@@ -0,0 +1,30 @@
+pub mod answers;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bench;
+pub mod cycle;
+pub mod day1;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod output;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pool;
+pub mod registry;
+pub mod repl;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod scaffold;
+pub mod solution;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod verify;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watch;
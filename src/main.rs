@@ -1,15 +1,493 @@
-mod day1;
-mod day14;
-mod day15;
-mod day16;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-
-fn main() {
-    day16::solve();
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::{builder::RangedU64ValueParser, ArgAction, Args, Parser, Subcommand};
+use tracing_subscriber::EnvFilter;
+
+use advent_of_code_2023::{
+    answers::Answers,
+    bench,
+    input::Source,
+    output::{self, format_duration, Format, Output},
+    pool,
+    registry::{self, Day},
+    repl, scaffold,
+    verify::{self, Check, Outcome},
+    watch::{self, Rebuild},
+};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Log solver diagnostics to stderr, -v for debug events and -vv for every trace event
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Log every trace event of a day, for example `--trace day7`
+    #[arg(long, global = true, value_name = "DAY", value_parser = parse_day_name)]
+    trace: Vec<u8>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a single day, or every day with --all
+    Run(RunArgs),
+    /// Check every day against the stored answers
+    Verify(VerifyArgs),
+    /// Time the parse, part 1 and part 2 phases of every day
+    Bench(BenchArgs),
+    /// Create and register the module of a new day
+    NewDay(NewDayArgs),
+    /// Load a day's input and explore its parsed state command by command
+    Repl(ReplArgs),
+    /// Solve a day again every time its source or input changes
+    Watch(WatchArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Day to solve
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u8>,
+
+    /// Solve only this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Solve every available day
+    #[arg(long, conflicts_with = "day")]
+    all: bool,
+
+    /// Read the input from this file instead of the inputs directory, `-` reads stdin
+    #[arg(short, long, conflicts_with = "all")]
+    input: Option<PathBuf>,
+
+    /// How the answers are printed
+    #[arg(short, long, value_enum, default_value_t)]
+    format: Format,
+
+    /// How many days are solved at the same time, defaults to the number of CPUs
+    #[arg(short, long, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    jobs: Option<usize>,
+
+    #[command(flatten)]
+    inputs: InputsDir,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Verify only this day
+    #[arg(short, long)]
+    day: Option<u8>,
+
+    /// TOML file with the accepted answers
+    #[arg(long, default_value = "answers.toml")]
+    answers: PathBuf,
+
+    #[command(flatten)]
+    inputs: InputsDir,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Benchmark only this day
+    #[arg(short, long)]
+    day: Option<u8>,
+
+    /// Benchmark only this part, the input is parsed either way
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// How many times every phase is run
+    #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+
+    /// Also write the timings as JSON to this file, `-` writes them to stdout instead of the table
+    #[arg(long)]
+    json: Option<PathBuf>,
+
+    #[command(flatten)]
+    inputs: InputsDir,
+}
+
+#[derive(Args)]
+struct NewDayArgs {
+    /// Day to create
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+
+    /// Source directory of the crate, holding lib.rs and registry.rs
+    #[arg(long, default_value = "src")]
+    src: PathBuf,
+}
+
+#[derive(Args)]
+struct ReplArgs {
+    /// Day to explore
+    #[arg(short, long)]
+    day: u8,
+
+    /// Read the input from this file instead of the inputs directory
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    #[command(flatten)]
+    inputs: InputsDir,
+}
+
+#[derive(Args)]
+struct WatchArgs {
+    /// Day to watch
+    #[arg(short, long)]
+    day: u8,
+
+    /// Read the input from this file instead of the inputs directory
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Also run the unit tests of the day after every change
+    #[arg(long)]
+    tests: bool,
+
+    /// Build the solutions with optimizations
+    #[arg(long)]
+    release: bool,
+
+    /// How often the files are checked, in milliseconds
+    #[arg(long, default_value_t = 500, value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
+
+    /// Source directory of the crate, holding a dayN directory for every day
+    #[arg(long, default_value = "src")]
+    src: PathBuf,
+
+    #[command(flatten)]
+    inputs: InputsDir,
+}
+
+#[derive(Args)]
+struct InputsDir {
+    /// Directory holding a dayN/input.txt for every day
+    #[arg(long, env = "AOC_INPUTS_DIR", default_value = "src")]
+    inputs_dir: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_logging(cli.verbose, &cli.trace);
+
+    match cli.command {
+        Command::Run(args) => run(args),
+        Command::Verify(args) => verify(args),
+        Command::Bench(args) => bench(args),
+        Command::NewDay(args) => new_day(args),
+        Command::Repl(args) => repl(args),
+        Command::Watch(args) => watch(args),
+    }
+}
+
+// "day7" or "7"
+fn parse_day_name(name: &str) -> Result<u8, String> {
+    name.strip_prefix("day")
+        .unwrap_or(name)
+        .parse()
+        .map_err(|_| format!("expected a day like \"day7\" or \"7\", found \"{name}\""))
+}
+
+const LIB: &str = "advent_of_code_2023";
+
+// --verbose and --trace take precedence over RUST_LOG, without any of them only errors are logged
+fn init_logging(verbose: u8, trace: &[u8]) {
+    let filter = if verbose == 0 && trace.is_empty() {
+        EnvFilter::from_default_env()
+    } else {
+        let level = match verbose {
+            0 => "warn",
+            1 => "debug",
+            _ => "trace",
+        };
+
+        let directives = trace
+            .iter()
+            .map(|day| format!("{LIB}::day{day}=trace"))
+            .chain([format!("{LIB}={level}")])
+            .collect::<Vec<_>>();
+
+        EnvFilter::new(directives.join(","))
+    };
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .init();
+}
+
+// the given day, or every day when none is given
+fn select_days(number: Option<u8>) -> Option<Vec<&'static Day>> {
+    let Some(number) = number else {
+        return Some(registry::DAYS.iter().collect());
+    };
+
+    let day = registry::get(number);
+    if day.is_none() {
+        eprintln!("day {number} is not solved yet");
+    }
+
+    day.map(|day| vec![day])
+}
+
+fn run(args: RunArgs) -> ExitCode {
+    let Some(days) = select_days(args.day) else {
+        return ExitCode::FAILURE;
+    };
+
+    let source = match args.input {
+        Some(path) => Source::File(path),
+        None => Source::Dir(args.inputs.inputs_dir),
+    };
+
+    let jobs = args
+        .jobs
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1);
+
+    let start = Instant::now();
+    let runs = pool::map(&days, jobs, |day| {
+        output::solve_day(day, &source, args.part)
+    });
+    let total = start.elapsed();
+
+    let mut output = Output::new(args.format, io::stdout().lock());
+    let mut failed = false;
+
+    for run in &runs {
+        for record in &run.records {
+            if let Err(e) = output.write(record) {
+                eprintln!("could not write answer: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+
+        for error in &run.errors {
+            eprintln!("{error}");
+            failed = true;
+        }
+    }
+
+    if args.all {
+        for run in &runs {
+            eprintln!("day {:>2} took {}", run.day, format_duration(run.elapsed));
+        }
+        eprintln!(
+            "{} days took {} on {jobs} workers",
+            runs.len(),
+            format_duration(total)
+        );
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn verify(args: VerifyArgs) -> ExitCode {
+    let answers = match Answers::load(&args.answers) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("could not read answers: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(days) = select_days(args.day) else {
+        return ExitCode::FAILURE;
+    };
+
+    let (mut passed, mut failed) = (0, 0);
+
+    for day in days {
+        let checks = verify::check_day(day, &answers, &args.inputs.inputs_dir);
+
+        if checks.is_empty() {
+            println!("day {}: no stored answers", day.number);
+        }
+
+        for check in checks {
+            let Check {
+                day, part, input, ..
+            } = &check;
+
+            match &check.outcome {
+                Outcome::Pass => println!("day {day} part {part} ({input}): ok"),
+                Outcome::Mismatch { expected, actual } => {
+                    println!("day {day} part {part} ({input}): FAILED");
+                    println!("  - {expected}");
+                    println!("  + {actual}");
+                }
+                Outcome::Failed(e) => println!("day {day} part {part} ({input}): FAILED, {e}"),
+            }
+
+            if check.passed() {
+                passed += 1;
+            } else {
+                failed += 1;
+            }
+        }
+    }
+
+    println!("{passed} passed, {failed} failed");
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn bench(args: BenchArgs) -> ExitCode {
+    let Some(days) = select_days(args.day) else {
+        return ExitCode::FAILURE;
+    };
+
+    let source = Source::Dir(args.inputs.inputs_dir);
+    let table = args.json.as_deref() != Some("-".as_ref());
+    let mut timings = vec![];
+    let mut failed = false;
+
+    if table {
+        println!("{}", bench::table_header());
+    }
+
+    for day in days {
+        let number = day.number;
+
+        let result = source
+            .read(number)
+            .map_err(|e| format!("could not read input: {e}"))
+            .and_then(|input| {
+                bench::bench(day, &input, args.runs as usize, args.part).map_err(|e| e.to_string())
+            });
+
+        let day_timings = match result {
+            Ok(day_timings) => day_timings,
+            Err(e) => {
+                eprintln!("day {number}: {e}");
+                failed = true;
+                continue;
+            }
+        };
+
+        if table {
+            for row in day_timings.table_rows() {
+                println!("{row}");
+            }
+        }
+
+        timings.push(day_timings);
+    }
+
+    if let Some(path) = args.json {
+        let json = serde_json::to_string_pretty(&timings).expect("timings serialize to JSON");
+
+        if path.as_os_str() == "-" {
+            println!("{json}");
+        } else if let Err(e) = fs::write(&path, json + "\n") {
+            eprintln!("could not write {}: {e}", path.display());
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn new_day(args: NewDayArgs) -> ExitCode {
+    match scaffold::new_day(&args.src, args.day) {
+        Ok(files) => {
+            for file in files {
+                println!("wrote {}", file.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("could not create day {}: {e}", args.day);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn repl(args: ReplArgs) -> ExitCode {
+    let Some(explorer) = repl::get(args.day) else {
+        let days = repl::EXPLORERS
+            .iter()
+            .map(|explorer| explorer.day.to_string())
+            .collect::<Vec<_>>();
+        eprintln!(
+            "day {} can't be explored, try one of {}",
+            args.day,
+            days.join(", ")
+        );
+        return ExitCode::FAILURE;
+    };
+
+    let source = match args.input {
+        Some(path) => Source::File(path),
+        None => Source::Dir(args.inputs.inputs_dir),
+    };
+    let input = match source.read(args.day) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("could not read input: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match repl::run(explorer, &input, io::stdin().lock(), io::stdout()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("day {}: {e}", args.day);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn watch(args: WatchArgs) -> ExitCode {
+    let source_dir = args.src.join(format!("day{}", args.day));
+    let input = match args.input {
+        Some(path) => path,
+        None => Source::Dir(args.inputs.inputs_dir).path(args.day),
+    };
+    if input == Path::new("-") {
+        eprintln!("stdin can't be watched, give an input file");
+        return ExitCode::FAILURE;
+    }
+
+    let rebuild = Rebuild {
+        day: args.day,
+        input,
+        release: args.release,
+        tests: args.tests,
+    };
+    let interval = Duration::from_millis(args.interval);
+
+    match watch::watch(&rebuild, &source_dir, interval, io::stdout()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("could not write output: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,243 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use clap::ValueEnum;
+use serde::{Serialize, Serializer};
+
+use crate::{input::Source, registry::Day};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(ValueEnum))]
+pub enum Format {
+    /// `day 1 part 1: 54634`
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+    /// Comma separated, with a header row
+    Csv,
+}
+
+/// The answer to one part of a day.
+#[derive(Debug, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    /// Where the input was read from
+    pub input: String,
+    /// Time spent answering the part, without parsing
+    #[serde(rename = "elapsed_ns", serialize_with = "nanos")]
+    pub elapsed: Duration,
+}
+
+/// Serializes a duration as whole nanoseconds.
+pub(crate) fn nanos<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos() as u64)
+}
+
+/// Durations as they are shown to people, like `1.50ms`.
+pub fn format_duration(duration: Duration) -> String {
+    format!("{duration:.2?}")
+}
+
+/// The answers of one day and everything that went wrong getting them.
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u8,
+    pub records: Vec<Record>,
+    pub errors: Vec<String>,
+    /// Time spent reading, parsing and answering
+    pub elapsed: Duration,
+}
+
+/// Reads the input of `day` from `source` and answers both parts, or only `part` when it is
+/// given. A failing part does not keep the other from being answered.
+pub fn solve_day(day: &Day, source: &Source, part: Option<u8>) -> DayRun {
+    let start = Instant::now();
+    let number = day.number;
+    let mut run = DayRun {
+        day: number,
+        records: vec![],
+        errors: vec![],
+        elapsed: Duration::ZERO,
+    };
+
+    let path = source.path(number);
+    let input_name = if path == Path::new("-") {
+        "stdin".to_string()
+    } else {
+        path.display().to_string()
+    };
+
+    let parsed = source
+        .read(number)
+        .map_err(|e| format!("could not read input: {e}"))
+        .and_then(|input| day.parse(&input).map_err(|e| e.to_string()));
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            run.errors.push(format!("day {number}: {e}"));
+            run.elapsed = start.elapsed();
+            return run;
+        }
+    };
+
+    for p in [1, 2] {
+        if part.is_some_and(|part| part != p) {
+            continue;
+        }
+
+        let part_start = Instant::now();
+        match day.answer(parsed.as_ref(), p) {
+            Ok(answer) => run.records.push(Record {
+                day: number,
+                part: p,
+                answer,
+                input: input_name.clone(),
+                elapsed: part_start.elapsed(),
+            }),
+            Err(e) => run.errors.push(format!("day {number} part {p}: {e}")),
+        }
+    }
+
+    run.elapsed = start.elapsed();
+    run
+}
+
+/// Writes records in a [`Format`], starting with a header when the format has one.
+pub struct Output<W: Write> {
+    format: Format,
+    out: W,
+    started: bool,
+}
+
+impl<W: Write> Output<W> {
+    pub fn new(format: Format, out: W) -> Self {
+        Output {
+            format,
+            out,
+            started: false,
+        }
+    }
+
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        if !self.started && self.format == Format::Csv {
+            writeln!(self.out, "day,part,answer,input,elapsed_ns")?;
+        }
+        self.started = true;
+
+        match self.format {
+            Format::Text => writeln!(
+                self.out,
+                "day {} part {}: {}",
+                record.day, record.part, record.answer
+            ),
+            Format::Json => {
+                serde_json::to_writer(&mut self.out, record)?;
+                writeln!(self.out)
+            }
+            Format::Csv => writeln!(
+                self.out,
+                "{},{},{},{},{}",
+                record.day,
+                record.part,
+                csv_field(&record.answer),
+                csv_field(&record.input),
+                record.elapsed.as_nanos()
+            ),
+        }
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn records() -> [Record; 2] {
+        [
+            Record {
+                day: 1,
+                part: 1,
+                answer: "54634".into(),
+                input: "day1/input.txt".into(),
+                elapsed: Duration::from_micros(15),
+            },
+            Record {
+                day: 1,
+                part: 2,
+                answer: "a,\"b\"".into(),
+                input: "stdin".into(),
+                elapsed: Duration::from_nanos(7),
+            },
+        ]
+    }
+
+    fn write_all(format: Format) -> String {
+        let mut output = Output::new(format, Vec::new());
+        for record in records() {
+            output.write(&record).unwrap();
+        }
+
+        String::from_utf8(output.out).unwrap()
+    }
+
+    #[test]
+    fn solve_day_works() {
+        let day = registry::get(15).unwrap();
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+        let run = solve_day(day, &Source::Dir(dir.clone()), Some(1));
+        assert_eq!(run.records.len(), 1);
+        assert_eq!(run.records[0].answer, "510013");
+        assert!(run.errors.is_empty());
+
+        let run = solve_day(day, &Source::Dir(dir.join("missing")), None);
+        assert!(run.records.is_empty());
+        assert_eq!(run.errors.len(), 1);
+        assert!(run.errors[0].starts_with("day 15: could not read input"));
+    }
+
+    #[test]
+    fn output_text_works() {
+        assert_eq!(
+            write_all(Format::Text),
+            "day 1 part 1: 54634\nday 1 part 2: a,\"b\"\n"
+        );
+    }
+
+    #[test]
+    fn output_json_works() {
+        assert_eq!(
+            write_all(Format::Json),
+            r#"{"day":1,"part":1,"answer":"54634","input":"day1/input.txt","elapsed_ns":15000}
+{"day":1,"part":2,"answer":"a,\"b\"","input":"stdin","elapsed_ns":7}
+"#
+        );
+    }
+
+    #[test]
+    fn output_csv_works() {
+        assert_eq!(
+            write_all(Format::Csv),
+            r#"day,part,answer,input,elapsed_ns
+1,1,54634,day1/input.txt,15000
+1,2,"a,""b""",stdin,7
+"#
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+/// Runs `f` on every item on up to `jobs` worker threads and returns the results in the order
+/// of the items, whichever worker finished first.
+pub fn map<T, R>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+    let workers = jobs.clamp(1, items.len().max(1));
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+
+                let result = f(item);
+                results.lock().unwrap().push((i, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(i, _)| *i);
+
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn map_keeps_item_order() {
+        let items = [30, 0, 20, 10, 5];
+
+        let results = map(&items, 3, |ms| {
+            thread::sleep(Duration::from_millis(*ms));
+            ms * 2
+        });
+
+        assert_eq!(results, vec![60, 0, 40, 20, 10]);
+    }
+
+    #[test]
+    fn map_handles_edge_cases() {
+        assert_eq!(map(&[1, 2], 0, |n| n + 1), vec![2, 3]);
+        assert!(map(&[] as &[u8], 4, |n| *n).is_empty());
+    }
+}
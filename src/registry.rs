@@ -0,0 +1,113 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+};
+
+use tracing::debug_span;
+
+use crate::{
+    day1, day14, day15, day16, day2, day3, day4, day5, day6, day7, day8, error::SolveError, input,
+    solution::Solver,
+};
+
+pub struct Day {
+    pub number: u8,
+    pub solver: &'static dyn Solver,
+}
+
+/// Every solved day, in puzzle order.
+pub const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        solver: &day1::Day1,
+    },
+    Day {
+        number: 2,
+        solver: &day2::Day2,
+    },
+    Day {
+        number: 3,
+        solver: &day3::Day3,
+    },
+    Day {
+        number: 4,
+        solver: &day4::Day4,
+    },
+    Day {
+        number: 5,
+        solver: &day5::Day5,
+    },
+    Day {
+        number: 6,
+        solver: &day6::Day6,
+    },
+    Day {
+        number: 7,
+        solver: &day7::Day7,
+    },
+    Day {
+        number: 8,
+        solver: &day8::Day8,
+    },
+    Day {
+        number: 14,
+        solver: &day14::Day14,
+    },
+    Day {
+        number: 15,
+        solver: &day15::Day15,
+    },
+    Day {
+        number: 16,
+        solver: &day16::Day16,
+    },
+];
+
+impl Day {
+    /// Normalizes and parses `input`, turning a panicking parser into an error.
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Any>, SolveError> {
+        let _span = debug_span!("parse", day = self.number).entered();
+        let input = input::normalize(input);
+
+        panic::catch_unwind(AssertUnwindSafe(|| self.solver.parse(&input)))
+            .map_err(SolveError::from_panic)?
+            .map_err(SolveError::from)
+    }
+
+    /// Answers `part` from input returned by [`Day::parse`], turning a panicking solver into an error.
+    /// Fails for parts other than 1 and 2.
+    pub fn answer(&self, parsed: &dyn Any, part: u8) -> Result<String, SolveError> {
+        let _span = debug_span!("answer", day = self.number, part).entered();
+
+        panic::catch_unwind(AssertUnwindSafe(|| match part {
+            1 => self.solver.part1(parsed),
+            2 => self.solver.part2(parsed),
+            _ => Err(SolveError::NoAnswer(format!("there is no part {part}"))),
+        }))
+        .map_err(SolveError::from_panic)?
+    }
+}
+
+pub fn get(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_rejects_unknown_parts() {
+        let day = get(15).unwrap();
+        let parsed = day.parse("rn=1").unwrap();
+
+        assert_eq!(day.answer(parsed.as_ref(), 1).unwrap(), "30");
+        assert_eq!(day.answer(parsed.as_ref(), 2).unwrap(), "1");
+        for part in [0, 3] {
+            assert!(matches!(
+                day.answer(parsed.as_ref(), part),
+                Err(SolveError::NoAnswer(reason)) if reason == format!("there is no part {part}")
+            ));
+        }
+    }
+}
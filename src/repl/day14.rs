@@ -0,0 +1,88 @@
+use super::{count, unknown, Session};
+use crate::{
+    day14::{self, Board},
+    error::ParseError,
+    grid::Dir,
+};
+
+struct BoardSession {
+    board: Board,
+    // spin cycles done since the input was read
+    cycles: usize,
+}
+
+pub fn open(input: &str) -> Result<Box<dyn Session>, ParseError> {
+    Ok(Box::new(BoardSession {
+        board: input.parse()?,
+        cycles: 0,
+    }))
+}
+
+fn parse_dir(name: &str) -> Option<Dir> {
+    match name {
+        "north" | "n" => Some(Dir::Up),
+        "west" | "w" => Some(Dir::Left),
+        "south" | "s" => Some(Dir::Down),
+        "east" | "e" => Some(Dir::Right),
+        _ => None,
+    }
+}
+
+impl Session for BoardSession {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("tilt <dir>", "tilt north, west, south or east"),
+            (
+                "cycle [n]",
+                "run n spin cycles, tilting north, west, south and east",
+            ),
+            ("show", "print the board"),
+            ("load", "print the load on the north beams"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "tilt" => {
+                let dir = match args {
+                    [name] => parse_dir(name),
+                    _ => None,
+                }
+                .ok_or("expected north, west, south or east")?;
+
+                self.board = self.board.tilt(dir);
+                Ok(self.board.to_string())
+            }
+            "cycle" => {
+                for _ in 0..count(args)? {
+                    self.board = day14::spin(&self.board);
+                    self.cycles += 1;
+                }
+
+                Ok(format!("after {} cycles\n{}", self.cycles, self.board))
+            }
+            "show" => Ok(self.board.to_string()),
+            "load" => Ok(self.board.get_load().to_string()),
+            _ => Err(unknown(command)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_session_works() {
+        let mut session = open("O.#\n.O.\nO..").unwrap();
+
+        assert_eq!(session.run("load", &[]).unwrap(), "6");
+        assert_eq!(session.run("tilt", &["n"]).unwrap(), "OO#\nO..\n...");
+        assert_eq!(session.run("load", &[]).unwrap(), "8");
+        assert_eq!(
+            session.run("cycle", &["2"]).unwrap(),
+            "after 2 cycles\n.O#\n..O\n..O"
+        );
+        assert!(session.run("tilt", &["up"]).is_err());
+    }
+}
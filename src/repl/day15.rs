@@ -0,0 +1,119 @@
+use std::str::FromStr;
+
+use super::{count, unknown, Session};
+use crate::{
+    day15::{self, hash, Command, LensBox, Machine},
+    error::ParseError,
+};
+
+struct MachineSession {
+    commands: Vec<Command>,
+    // how many of the commands were applied
+    next: usize,
+    machine: Machine,
+}
+
+pub fn open(input: &str) -> Result<Box<dyn Session>, ParseError> {
+    Ok(Box::new(MachineSession {
+        commands: day15::parse(input)?,
+        next: 0,
+        machine: Machine::new(),
+    }))
+}
+
+fn render_box(i: usize, lens_box: &LensBox) -> String {
+    let lenses = lens_box
+        .lenses
+        .iter()
+        .map(|lens| format!(" [{} {}]", lens.label, lens.focal))
+        .collect::<String>();
+
+    format!("box {i}:{lenses}")
+}
+
+impl Session for MachineSession {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("step [n]", "apply the next n steps of the input"),
+            ("apply <step>", "apply a step of your own, like cm-"),
+            ("show", "print the boxes holding lenses"),
+            ("power", "print the focusing power"),
+            ("hash <text>", "print the HASH of the text"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "step" => {
+                let n = count(args)?;
+                if self.next == self.commands.len() {
+                    return Err("every step of the input was applied".into());
+                }
+
+                let end = (self.next + n).min(self.commands.len());
+                let applied = (self.next..end)
+                    .map(|i| {
+                        self.machine.apply(&self.commands[i]);
+                        format!("{}/{} {}", i + 1, self.commands.len(), self.commands[i])
+                    })
+                    .collect::<Vec<_>>();
+                self.next = end;
+
+                Ok(applied.join("\n"))
+            }
+            "apply" => {
+                let [step] = args else {
+                    return Err("expected a step, like cm- or rn=1".into());
+                };
+                let command = Command::from_str(step).map_err(|e| e.to_string())?;
+                self.machine.apply(&command);
+
+                let i = hash(&command.label) as usize;
+                Ok(render_box(i, &self.machine.boxes[i]))
+            }
+            "show" => {
+                let boxes = self
+                    .machine
+                    .boxes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, lens_box)| !lens_box.lenses.is_empty())
+                    .map(|(i, lens_box)| render_box(i, lens_box))
+                    .collect::<Vec<_>>();
+
+                if boxes.is_empty() {
+                    Ok("every box is empty".into())
+                } else {
+                    Ok(boxes.join("\n"))
+                }
+            }
+            "power" => Ok(self.machine.get_focusing_power().to_string()),
+            "hash" => Ok(hash(&args.join(" ")).to_string()),
+            _ => Err(unknown(command)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn machine_session_works() {
+        let mut session = open("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7").unwrap();
+
+        assert_eq!(
+            session.run("step", &["4"]).unwrap(),
+            "1/11 rn=1\n2/11 cm-\n3/11 qp=3\n4/11 cm=2"
+        );
+        assert_eq!(
+            session.run("show", &[]).unwrap(),
+            "box 0: [rn 1] [cm 2]\nbox 1: [qp 3]"
+        );
+        assert_eq!(session.run("apply", &["qp-"]).unwrap(), "box 1:");
+        assert_eq!(session.run("step", &["20"]).unwrap().lines().count(), 7);
+        assert_eq!(session.run("power", &[]).unwrap(), "145");
+        assert!(session.run("step", &[]).is_err());
+        assert_eq!(session.run("hash", &["HASH"]).unwrap(), "52");
+    }
+}
@@ -0,0 +1,152 @@
+use super::{count, unknown, Session};
+use crate::{
+    day8::{Direction, Node, Simulation},
+    error::ParseError,
+};
+
+struct SimulationSession {
+    sim: Simulation,
+    current: String,
+    // instructions followed since the walk started at its node
+    steps: usize,
+}
+
+pub fn open(input: &str) -> Result<Box<dyn Session>, ParseError> {
+    let sim: Simulation = input.parse()?;
+
+    // AAA when there is one, parsing makes sure there is a node
+    let current = if sim.graph.contains_key("AAA") {
+        "AAA".to_string()
+    } else {
+        sim.graph.keys().min().cloned().unwrap_or_default()
+    };
+
+    Ok(Box::new(SimulationSession {
+        sim,
+        current,
+        steps: 0,
+    }))
+}
+
+impl SimulationSession {
+    fn node(&self) -> &Node {
+        &self.sim.graph[&self.current]
+    }
+
+    fn advance(&mut self) {
+        self.current = self.sim.get_next(self.steps, self.node()).text.clone();
+        self.steps += 1;
+    }
+
+    fn state(&self) -> String {
+        let len = self.sim.instructions.len();
+        let next = match self.sim.instructions[self.steps % len] {
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        };
+
+        format!(
+            "step {} at {}, next instruction {next} ({}/{len})",
+            self.steps,
+            self.current,
+            self.steps % len + 1
+        )
+    }
+}
+
+impl Session for SimulationSession {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("step [n]", "follow the next n instructions"),
+            ("run", "follow instructions until a node ending in Z"),
+            ("start <node>", "start walking again from the node"),
+            (
+                "node [name]",
+                "print where a node leads, the current one by default",
+            ),
+            ("show", "print the current node and the next instruction"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "step" => {
+                for _ in 0..count(args)? {
+                    self.advance();
+                }
+
+                Ok(self.state())
+            }
+            "run" => {
+                // after this many steps the walk only goes around in circles
+                let limit = self.sim.graph.len() * self.sim.instructions.len();
+
+                for _ in 0..=limit {
+                    self.advance();
+                    if self.current.ends_with('Z') {
+                        return Ok(self.state());
+                    }
+                }
+
+                Err(format!("no node ending in Z is reached, {}", self.state()))
+            }
+            "start" => {
+                let [name] = args else {
+                    return Err("expected a node".into());
+                };
+                if !self.sim.graph.contains_key(*name) {
+                    return Err(format!("there is no node {name}"));
+                }
+
+                self.current = name.to_string();
+                self.steps = 0;
+                Ok(self.state())
+            }
+            "node" => {
+                let node = match args {
+                    [] => self.node(),
+                    [name] => self
+                        .sim
+                        .graph
+                        .get(*name)
+                        .ok_or_else(|| format!("there is no node {name}"))?,
+                    _ => return Err("expected at most one node".into()),
+                };
+
+                Ok(format!("{} = ({}, {})", node.text, node.left, node.right))
+            }
+            "show" => Ok(self.state()),
+            _ => Err(unknown(command)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulation_session_works() {
+        let mut session =
+            open("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap();
+
+        assert_eq!(
+            session.run("show", &[]).unwrap(),
+            "step 0 at AAA, next instruction L (1/3)"
+        );
+        assert_eq!(
+            session.run("step", &["2"]).unwrap(),
+            "step 2 at AAA, next instruction R (3/3)"
+        );
+        assert_eq!(
+            session.run("run", &[]).unwrap(),
+            "step 6 at ZZZ, next instruction L (1/3)"
+        );
+        assert_eq!(session.run("node", &["BBB"]).unwrap(), "BBB = (AAA, ZZZ)");
+        assert!(session.run("start", &["CCC"]).is_err());
+        assert_eq!(
+            session.run("start", &["BBB"]).unwrap(),
+            "step 0 at BBB, next instruction L (1/3)"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+mod day14;
+mod day15;
+mod day8;
+
+use std::io::{self, BufRead, Write};
+
+use crate::{error::ParseError, input};
+
+/// The parsed state of a day that can be changed and inspected one command at a time.
+pub trait Session {
+    /// `(usage, description)` of every command the session understands
+    fn commands(&self) -> &'static [(&'static str, &'static str)];
+
+    /// Runs `command` with its `args`, returning what to print.
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String>;
+}
+
+pub struct Explorer {
+    pub day: u8,
+    pub open: fn(&str) -> Result<Box<dyn Session>, ParseError>,
+}
+
+/// Every day that can be explored, in puzzle order.
+pub const EXPLORERS: &[Explorer] = &[
+    Explorer {
+        day: 8,
+        open: day8::open,
+    },
+    Explorer {
+        day: 14,
+        open: day14::open,
+    },
+    Explorer {
+        day: 15,
+        open: day15::open,
+    },
+];
+
+pub fn get(day: u8) -> Option<&'static Explorer> {
+    EXPLORERS.iter().find(|explorer| explorer.day == day)
+}
+
+const BUILTIN: &[(&str, &str)] = &[
+    ("help", "list the commands"),
+    ("reset", "parse the input again, dropping every change"),
+    ("quit", "leave the shell"),
+];
+
+/// Reads commands from `input` until `quit` or the end of it, printing a prompt before every
+/// command and the results to `output`.
+pub fn run(
+    explorer: &Explorer,
+    puzzle_input: &str,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut session = open(explorer, puzzle_input)?;
+    let mut lines = input.lines();
+
+    loop {
+        write!(output, "day{}> ", explorer.day)?;
+        output.flush()?;
+
+        let Some(line) = lines.next().transpose()? else {
+            writeln!(output)?;
+            return Ok(());
+        };
+
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        let args = words.collect::<Vec<_>>();
+
+        match command {
+            "quit" | "exit" => return Ok(()),
+            "help" => {
+                for (usage, description) in session.commands().iter().chain(BUILTIN) {
+                    writeln!(output, "  {usage:<16} {description}")?;
+                }
+            }
+            "reset" => {
+                session = open(explorer, puzzle_input)?;
+            }
+            _ => match session.run(command, &args) {
+                Ok(result) if result.is_empty() => {}
+                Ok(result) => writeln!(output, "{result}")?,
+                Err(e) => writeln!(output, "error: {e}")?,
+            },
+        }
+    }
+}
+
+fn open(explorer: &Explorer, puzzle_input: &str) -> io::Result<Box<dyn Session>> {
+    (explorer.open)(&input::normalize(puzzle_input))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+// the optional count argument of commands like `step [n]`, 1 when it is left out
+fn count(args: &[&str]) -> Result<usize, String> {
+    match args {
+        [] => Ok(1),
+        [n] => n.parse().map_err(|_| format!("\"{n}\" is not a count")),
+        _ => Err("expected at most one count".into()),
+    }
+}
+
+fn unknown(command: &str) -> String {
+    format!("unknown command \"{command}\", try help")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript(day: u8, puzzle_input: &str, commands: &str) -> String {
+        let mut output = Vec::new();
+        run(
+            get(day).unwrap(),
+            puzzle_input,
+            commands.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn run_handles_builtin_commands() {
+        let output = transcript(15, "rn=1", "\nhelp\nbogus\nquit\nstep");
+
+        assert!(output.starts_with("day15> day15>   "));
+        assert!(output.contains("  reset            parse the input again"));
+        assert!(output.contains("error: unknown command \"bogus\", try help"));
+        assert!(!output.contains("rn=1"), "stops at quit");
+    }
+
+    #[test]
+    fn run_resets_the_session() {
+        let output = transcript(15, "rn=1", "step\npower\nreset\npower\n");
+
+        assert_eq!(
+            output,
+            "day15> 1/1 rn=1\nday15> 1\nday15> day15> 0\nday15> \n"
+        );
+    }
+
+    #[test]
+    fn run_normalizes_the_input() {
+        let output = transcript(15, "rn=1\r\n\r\n", "step\n");
+
+        assert_eq!(output, "day15> 1/1 rn=1\nday15> \n");
+    }
+
+    #[test]
+    fn count_works() {
+        assert_eq!(count(&[]), Ok(1));
+        assert_eq!(count(&["12"]), Ok(12));
+        assert!(count(&["x"]).is_err());
+        assert!(count(&["1", "2"]).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const TEMPLATE: &str = r#"use std::fmt::Display;
+
+use crate::{
+    error::{ParseError, SolveError},
+    solution::Solution,
+};
+
+pub struct Day{N};
+
+impl Solution for Day{N} {
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok(input.lines().map(String::from).collect())
+    }
+
+    fn part1(lines: &Self::Parsed) -> Result<impl Display, SolveError> {
+        part_1(lines)
+    }
+
+    fn part2(lines: &Self::Parsed) -> Result<impl Display, SolveError> {
+        part_2(lines)
+    }
+}
+
+pub fn part_1(_lines: &[String]) -> Result<u64, SolveError> {
+    Err(SolveError::NoAnswer("part 1 is not solved yet".into()))
+}
+
+pub fn part_2(_lines: &[String]) -> Result<u64, SolveError> {
+    Err(SolveError::NoAnswer("part 2 is not solved yet".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    #[ignore = "add the example from the puzzle"]
+    fn part_1_works() {
+        let lines = Day{N}::parse(EXAMPLE).unwrap();
+
+        assert_eq!(part_1(&lines).unwrap(), 0);
+    }
+
+    #[test]
+    #[ignore = "add the example from the puzzle"]
+    fn part_2_works() {
+        let lines = Day{N}::parse(EXAMPLE).unwrap();
+
+        assert_eq!(part_2(&lines).unwrap(), 0);
+    }
+}
+"#;
+
+const MAX_WIDTH: usize = 100;
+
+/// Creates `dayN/mod.rs` and an empty `dayN/input.txt` under `src`, and registers the day in
+/// `lib.rs` and `registry.rs`. Returns the files it wrote.
+pub fn new_day(src: &Path, day: u8) -> io::Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(invalid(format!("day {day} is not between 1 and 25")));
+    }
+
+    let dir = src.join(format!("day{day}"));
+    if dir.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", dir.display()),
+        ));
+    }
+
+    let lib_path = src.join("lib.rs");
+    let registry_path = src.join("registry.rs");
+    let lib = add_module(&read(&lib_path)?, day)?;
+    let registry = add_to_registry(&read(&registry_path)?, day)?;
+
+    let module_path = dir.join("mod.rs");
+    let input_path = dir.join("input.txt");
+    fs::create_dir_all(&dir)?;
+    fs::write(&module_path, TEMPLATE.replace("{N}", &day.to_string()))?;
+    fs::write(&input_path, "")?;
+    fs::write(&lib_path, lib)?;
+    fs::write(&registry_path, registry)?;
+
+    Ok(vec![module_path, input_path, lib_path, registry_path])
+}
+
+fn read(path: &Path) -> io::Result<String> {
+    fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Adds `pub mod dayN;` to `lib.rs`, keeping the module list sorted like rustfmt does.
+pub fn add_module(lib: &str, day: u8) -> io::Result<String> {
+    let name = format!("day{day}");
+    let module = format!("pub mod {name};");
+    let mut lines = lib.lines().collect::<Vec<_>>();
+
+    if lines.contains(&module.as_str()) {
+        return Err(invalid(format!("lib.rs already declares {name}")));
+    }
+
+    let at = lines
+        .iter()
+        .position(|line| declared_module(line).is_some_and(|declared| declared > name.as_str()))
+        .or_else(|| {
+            lines
+                .iter()
+                .rposition(|line| declared_module(line).is_some())
+                .map(|i| i + 1)
+        })
+        .unwrap_or(lines.len());
+    lines.insert(at, &module);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Imports the day module in `registry.rs` and adds the day to `DAYS` in puzzle order.
+pub fn add_to_registry(registry: &str, day: u8) -> io::Result<String> {
+    let module = format!("day{day}");
+
+    // the `use crate::{...};` list holding the day modules
+    let start = registry
+        .find("use crate::{")
+        .ok_or_else(|| invalid("registry.rs has no `use crate::{...}`".to_string()))?;
+    let end = start
+        + registry[start..]
+            .find("};")
+            .ok_or_else(|| invalid("registry.rs has an unterminated use".to_string()))?
+        + 2;
+
+    let list = &registry[start + "use crate::{".len()..end - 2];
+    if list.contains('{') {
+        return Err(invalid(
+            "registry.rs has nested imports in `use crate::{...}`".to_string(),
+        ));
+    }
+
+    let mut items = list
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect::<Vec<_>>();
+
+    if items.contains(&module.as_str()) {
+        return Err(invalid(format!("registry.rs already imports {module}")));
+    }
+    items.push(&module);
+    items.sort();
+
+    let entry = format!(
+        "    Day {{\n        number: {day},\n        solver: &{module}::Day{day},\n    }},\n"
+    );
+
+    // before the first later day, or at the end of the list
+    let days_end = registry
+        .find("\n];")
+        .ok_or_else(|| invalid("registry.rs has no DAYS list".to_string()))?
+        + 1;
+    let at = registry[..days_end]
+        .match_indices("    Day {\n        number: ")
+        .find(|(i, prefix)| {
+            let number = &registry[i + prefix.len()..];
+            let number = &number[..number.find(',').unwrap_or(0)];
+
+            number.parse::<u8>().is_ok_and(|number| number > day)
+        })
+        .map_or(days_end, |(i, _)| i);
+
+    Ok(format!(
+        "{}{}{}{}{}",
+        &registry[..start],
+        format_use(&items),
+        &registry[end..at],
+        entry,
+        &registry[at..]
+    ))
+}
+
+// the module a `pub mod name;` line declares
+fn declared_module(line: &str) -> Option<&str> {
+    line.strip_prefix("pub mod ")?.strip_suffix(';')
+}
+
+// `use crate::{...};` laid out the way rustfmt does it
+fn format_use(items: &[&str]) -> String {
+    let one_line = format!("use crate::{{{}}};", items.join(", "));
+    if one_line.len() <= MAX_WIDTH {
+        return one_line;
+    }
+
+    let mut out = String::from("use crate::{\n");
+    let mut line = String::from("   ");
+
+    for item in items {
+        if line.len() > 3 && line.len() + item.len() + 2 > MAX_WIDTH {
+            out += &line;
+            out.push('\n');
+            line = String::from("   ");
+        }
+
+        line += &format!(" {item},");
+    }
+
+    out + &line + "\n};"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_module_keeps_order() {
+        let lib = "pub mod answers;\npub mod day1;\npub mod day2;\npub mod error;\n";
+
+        assert_eq!(
+            add_module(lib, 12).unwrap(),
+            "pub mod answers;\npub mod day1;\npub mod day12;\npub mod day2;\npub mod error;\n"
+        );
+        assert!(add_module(lib, 2).is_err());
+    }
+
+    #[test]
+    fn template_does_not_panic() {
+        assert!(!TEMPLATE.contains("todo!"));
+        assert!(!TEMPLATE.contains("unimplemented!"));
+    }
+
+    #[test]
+    fn format_use_matches_registry() {
+        let registry = include_str!("registry.rs");
+        let start = registry.find("use crate::{").unwrap();
+        let end = start + registry[start..].find("};").unwrap() + 2;
+        let list = &registry[start + "use crate::{".len()..end - 2];
+        let items = list
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .collect::<Vec<_>>();
+
+        assert_eq!(format_use(&items), &registry[start..end]);
+    }
+
+    #[test]
+    fn add_to_registry_inserts_in_puzzle_order() {
+        let registry = "use crate::{day1, day3, solution::Solver};
+
+pub const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        solver: &day1::Day1,
+    },
+    Day {
+        number: 3,
+        solver: &day3::Day3,
+    },
+];
+";
+
+        assert_eq!(
+            add_to_registry(registry, 2).unwrap(),
+            "use crate::{day1, day2, day3, solution::Solver};
+
+pub const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        solver: &day1::Day1,
+    },
+    Day {
+        number: 2,
+        solver: &day2::Day2,
+    },
+    Day {
+        number: 3,
+        solver: &day3::Day3,
+    },
+];
+"
+        );
+
+        let last = add_to_registry(registry, 9).unwrap();
+        assert!(last.ends_with("solver: &day9::Day9,\n    },\n];\n"));
+        assert!(add_to_registry(registry, 3).is_err());
+    }
+}
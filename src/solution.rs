@@ -0,0 +1,51 @@
+use std::{any::Any, fmt::Display};
+
+use crate::error::{ParseError, SolveError};
+
+/// A day's puzzle. The input is parsed once and both parts are answered from the parsed form.
+pub trait Solution {
+    type Parsed;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError>;
+
+    /// Fails when the input parses but has no answer, for example when it would overflow.
+    fn part1(parsed: &Self::Parsed) -> Result<impl Display, SolveError>;
+
+    fn part2(parsed: &Self::Parsed) -> Result<impl Display, SolveError>;
+}
+
+/// Object safe form of [`Solution`] so days with different parsed types can share a registry.
+pub trait Solver: Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+
+    fn part1(&self, parsed: &dyn Any) -> Result<String, SolveError>;
+
+    fn part2(&self, parsed: &dyn Any) -> Result<String, SolveError>;
+}
+
+impl<S> Solver for S
+where
+    S: Solution + Sync,
+    S::Parsed: 'static,
+{
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(<S as Solution>::parse(input)?))
+    }
+
+    fn part1(&self, parsed: &dyn Any) -> Result<String, SolveError> {
+        Ok(<S as Solution>::part1(downcast::<S>(parsed))?.to_string())
+    }
+
+    fn part2(&self, parsed: &dyn Any) -> Result<String, SolveError> {
+        Ok(<S as Solution>::part2(downcast::<S>(parsed))?.to_string())
+    }
+}
+
+fn downcast<S: Solution>(parsed: &dyn Any) -> &S::Parsed
+where
+    S::Parsed: 'static,
+{
+    parsed
+        .downcast_ref()
+        .expect("parsed input belongs to another day")
+}
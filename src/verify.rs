@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use crate::{
+    answers::Answers,
+    input::{input_path, Source},
+    registry::Day,
+};
+
+/// The result of checking one part of a day on one input against its stored answer.
+#[derive(Debug, PartialEq)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    /// Name of the input, `dayN/<input>.txt` in the inputs directory
+    pub input: String,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+    /// The input could not be read or parsed, or the solver panicked
+    Failed(String),
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        self.outcome == Outcome::Pass
+    }
+}
+
+/// Answers every input of `day` that has stored answers, reading them from `inputs_dir`,
+/// and compares the results. Only the parts with a stored answer are checked.
+pub fn check_day(day: &Day, answers: &Answers, inputs_dir: &Path) -> Vec<Check> {
+    let number = day.number;
+    let mut checks = Vec::new();
+
+    for name in answers.inputs(number) {
+        let expected = [1, 2].map(|part| answers.get(number, name, part));
+        let check = |part, outcome| Check {
+            day: number,
+            part,
+            input: name.to_string(),
+            outcome,
+        };
+
+        let parsed = Source::File(input_path(inputs_dir, number, name))
+            .read(number)
+            .map_err(|e| format!("could not read input: {e}"))
+            .and_then(|input| day.parse(&input).map_err(|e| e.to_string()));
+
+        for (part, expected) in (1..=2).zip(expected) {
+            let Some(expected) = expected else {
+                continue;
+            };
+
+            let outcome = match &parsed {
+                Err(e) => Outcome::Failed(e.clone()),
+                Ok(parsed) => match day.answer(parsed.as_ref(), part) {
+                    Ok(actual) if actual == expected => Outcome::Pass,
+                    Ok(actual) => Outcome::Mismatch { expected, actual },
+                    Err(e) => Outcome::Failed(e.to_string()),
+                },
+            };
+
+            checks.push(check(part, outcome));
+        }
+    }
+
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn check_day_reports_every_outcome() {
+        let answers = "[day15.missing]\npart1 = 1\n\n[day15.input]\npart1 = 510013\npart2 = 1"
+            .parse::<Answers>()
+            .unwrap();
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let checks = check_day(registry::get(15).unwrap(), &answers, &dir);
+
+        let outcomes = checks
+            .iter()
+            .map(|check| (check.input.as_str(), check.part, &check.outcome))
+            .collect::<Vec<_>>();
+
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[0], ("input", 1, &Outcome::Pass));
+        assert_eq!(
+            outcomes[1],
+            (
+                "input",
+                2,
+                &Outcome::Mismatch {
+                    expected: "1".into(),
+                    actual: "268497".into()
+                }
+            )
+        );
+        assert!(matches!(outcomes[2], ("missing", 1, Outcome::Failed(_))));
+    }
+}
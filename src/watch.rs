@@ -0,0 +1,274 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    ffi::OsString,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process, thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use serde_json::Value;
+
+use crate::output::format_duration;
+
+// what a file looked like the last time it was checked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+/// Polls files for changes, a directory standing for every file below it.
+pub struct Watcher {
+    roots: Vec<PathBuf>,
+    stamps: BTreeMap<PathBuf, Stamp>,
+}
+
+impl Watcher {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        let stamps = scan(&roots);
+        Watcher { roots, stamps }
+    }
+
+    /// Files created, changed or removed since the last check, in path order.
+    pub fn changes(&mut self) -> Vec<PathBuf> {
+        let stamps = scan(&self.roots);
+
+        let changed = stamps
+            .iter()
+            .filter(|(path, stamp)| self.stamps.get(*path) != Some(stamp))
+            .map(|(path, _)| path)
+            .chain(
+                self.stamps
+                    .keys()
+                    .filter(|path| !stamps.contains_key(*path)),
+            )
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        self.stamps = stamps;
+        changed.into_iter().collect()
+    }
+
+    /// Checks every `interval` until something changes, returning what did.
+    pub fn wait(&mut self, interval: Duration) -> Vec<PathBuf> {
+        loop {
+            thread::sleep(interval);
+
+            let changes = self.changes();
+            if !changes.is_empty() {
+                return changes;
+            }
+        }
+    }
+}
+
+fn scan(roots: &[PathBuf]) -> BTreeMap<PathBuf, Stamp> {
+    let mut stamps = BTreeMap::new();
+    for root in roots {
+        visit(root, &mut stamps);
+    }
+
+    stamps
+}
+
+// missing files are left out, so creating one later shows up as a change
+fn visit(path: &Path, stamps: &mut BTreeMap<PathBuf, Stamp>) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            visit(&entry.path(), stamps);
+        }
+    } else {
+        let stamp = Stamp {
+            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            len: metadata.len(),
+        };
+        stamps.insert(path.to_path_buf(), stamp);
+    }
+}
+
+/// How `aoc watch` builds and checks a day after every change.
+pub struct Rebuild {
+    pub day: u8,
+    /// The input the day is solved on
+    pub input: PathBuf,
+    /// Build with optimizations
+    pub release: bool,
+    /// Also run the unit tests of the day
+    pub tests: bool,
+}
+
+impl Rebuild {
+    /// Builds a fresh binary of the crate in the current directory, as the code may have
+    /// changed, and solves the day with it. Returns what to show.
+    pub fn run(&self) -> String {
+        let mut report = String::new();
+        let run = [
+            "run".to_string(),
+            format!("--day={}", self.day),
+            "--format=json".to_string(),
+            format!("--input={}", self.input.display()),
+        ];
+
+        let start = Instant::now();
+        match cargo("run", self.release, &["--bin", "aoc"], &run) {
+            Ok(output) => {
+                report += &format!("day {}\n", self.day);
+                report += &summarize(&String::from_utf8_lossy(&output.stdout));
+                report += "\n";
+                report += &String::from_utf8_lossy(&output.stderr);
+                report += &format!("built and solved in {}\n", format_duration(start.elapsed()));
+            }
+            Err(e) => report += &format!("could not run cargo: {e}\n"),
+        }
+
+        if self.tests {
+            let filter = format!("day{}::", self.day);
+            match cargo("test", self.release, &["--lib"], &[filter]) {
+                Ok(output) if output.status.success() => report += "\ntests passed\n",
+                Ok(output) => {
+                    report += "\ntests failed\n";
+                    report += &String::from_utf8_lossy(&output.stdout);
+                    report += &String::from_utf8_lossy(&output.stderr);
+                }
+                Err(e) => report += &format!("could not run cargo: {e}\n"),
+            }
+        }
+
+        report
+    }
+}
+
+/// Runs `rebuild` now and again every time a file below `source_dir` or its input changes,
+/// checking every `interval`. Only returns when writing to `out` fails.
+pub fn watch(
+    rebuild: &Rebuild,
+    source_dir: &Path,
+    interval: Duration,
+    mut out: impl Write,
+) -> io::Result<()> {
+    let mut watcher = Watcher::new(vec![source_dir.to_path_buf(), rebuild.input.clone()]);
+    let mut changes: Vec<PathBuf> = vec![];
+
+    loop {
+        // clear the screen so the answers stay in place
+        write!(out, "\x1b[2J\x1b[H")?;
+        writeln!(
+            out,
+            "watching {} and {}, ctrl-c to stop",
+            source_dir.display(),
+            rebuild.input.display()
+        )?;
+        for path in &changes {
+            writeln!(out, "changed {}", path.display())?;
+        }
+        writeln!(out)?;
+        out.flush()?;
+
+        write!(out, "{}", rebuild.run())?;
+        out.flush()?;
+
+        changes = watcher.wait(interval);
+    }
+}
+
+// runs `cargo <command>` on the crate of the current directory, waiting for it to finish
+fn cargo(
+    command: &str,
+    release: bool,
+    options: &[&str],
+    args: &[String],
+) -> io::Result<process::Output> {
+    let mut cargo = process::Command::new(env::var_os("CARGO").unwrap_or(OsString::from("cargo")));
+    cargo.arg(command).arg("--quiet").args(options);
+    if release {
+        cargo.arg("--release");
+    }
+
+    cargo.arg("--").args(args).output()
+}
+
+/// Turns the JSON lines printed by `aoc run --format json` into one `part N: answer (time)` line
+/// per part. Lines that are not records are kept as they are.
+pub fn summarize(json_lines: &str) -> String {
+    json_lines
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let Ok(record) = serde_json::from_str::<Value>(line) else {
+                return line.to_string();
+            };
+
+            match (
+                record["part"].as_u64(),
+                record["answer"].as_str(),
+                record["elapsed_ns"].as_u64(),
+            ) {
+                (Some(part), Some(answer), Some(nanos)) => {
+                    format!(
+                        "part {part}: {answer:<20} {:.2?}",
+                        Duration::from_nanos(nanos)
+                    )
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, process};
+
+    use super::*;
+
+    #[test]
+    fn watcher_notices_changes() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-{}", process::id()));
+        let input = dir.join("input.txt");
+        let source = dir.join("src").join("mod.rs");
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(&source, "fn main() {}").unwrap();
+
+        let mut watcher = Watcher::new(vec![dir.join("src"), input.clone()]);
+        assert!(watcher.changes().is_empty());
+
+        fs::write(&input, "1\n2").unwrap();
+        assert_eq!(watcher.changes(), vec![input.clone()]);
+
+        // same length, only the modification time tells the edit apart
+        fs::write(&source, "fn main(){ }")
+            .and_then(|_| File::options().write(true).open(&source))
+            .and_then(|file| file.set_modified(SystemTime::UNIX_EPOCH))
+            .unwrap();
+        assert_eq!(watcher.changes(), vec![source.clone()]);
+
+        fs::remove_file(&input).unwrap();
+        assert_eq!(watcher.changes(), vec![input]);
+        assert!(watcher.changes().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn summarize_works() {
+        let stdout = concat!(
+            r#"{"day":5,"part":1,"answer":"35","input":"input.txt","elapsed_ns":1500}"#,
+            "\n",
+            r#"{"day":5,"part":2,"answer":"46","input":"input.txt","elapsed_ns":2000000}"#,
+            "\n\nnot json\n"
+        );
+
+        assert_eq!(
+            summarize(stdout),
+            "part 1: 35                   1.50µs\npart 2: 46                   2.00ms\nnot json"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Runs every registered day on the puzzle examples in `fixtures/`.
+//!
+//! An example is `fixtures/dayN/<name>.txt` with its answers under `[dayN.<name>]` in
+//! `fixtures/answers.toml`, so adding a case needs no Rust code.
+
+use std::{fs, path::Path};
+
+use advent_of_code_2023::{
+    answers::Answers,
+    error::SolveError,
+    input::input_path,
+    registry::DAYS,
+    verify::{self, Outcome},
+};
+
+fn fixtures() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"))
+}
+
+fn answers() -> Answers {
+    Answers::load(&fixtures().join("answers.toml")).expect("fixtures/answers.toml loads")
+}
+
+#[test]
+fn examples_give_the_expected_answers() {
+    let answers = answers();
+    let mut failures = Vec::new();
+    let mut passed = 0;
+
+    for day in DAYS {
+        for check in verify::check_day(day, &answers, fixtures()) {
+            let name = format!("day {} part {} ({})", check.day, check.part, check.input);
+
+            match check.outcome {
+                Outcome::Pass => passed += 1,
+                Outcome::Mismatch { expected, actual } => {
+                    failures.push(format!("{name}: expected {expected}, got {actual}"))
+                }
+                Outcome::Failed(e) => failures.push(format!("{name}: {e}")),
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    assert!(passed > 0, "no examples were checked");
+}
+
+#[test]
+fn examples_parse_the_same_with_crlf_and_trailing_newlines() {
+    let answers = answers();
+    let mut failures = Vec::new();
+
+    for day in DAYS {
+        for name in answers.inputs(day.number) {
+            let path = input_path(fixtures(), day.number, name);
+            let input = fs::read_to_string(&path).unwrap();
+            let messy = input.replace('\n', "\r\n") + "\r\n\r\n";
+
+            let parsed = day.parse(&input);
+            let messy_parsed = day.parse(&messy);
+
+            for part in [1, 2] {
+                if answers.get(day.number, name, part).is_none() {
+                    continue;
+                }
+
+                let name = format!("{} part {part}", path.display());
+                let answer = |parsed: &Result<_, SolveError>| match parsed {
+                    Ok(parsed) => day
+                        .answer(Box::as_ref(parsed), part)
+                        .map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+
+                match (answer(&parsed), answer(&messy_parsed)) {
+                    (Err(e), _) => failures.push(format!("{name}: {e}")),
+                    (Ok(expected), Ok(actual)) if actual == expected => {}
+                    (Ok(expected), actual) => {
+                        failures.push(format!("{name}: expected {expected}, got {actual:?}"))
+                    }
+                }
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn every_example_has_answers() {
+    let answers = answers();
+    let mut missing = Vec::new();
+
+    for day in DAYS {
+        let Ok(entries) = fs::read_dir(fixtures().join(format!("day{}", day.number))) else {
+            continue;
+        };
+
+        for entry in entries {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "txt") {
+                continue;
+            }
+
+            let name = path.file_stem().unwrap().to_string_lossy();
+            if answers.inputs(day.number).all(|input| input != name) {
+                missing.push(path.display().to_string());
+            }
+        }
+    }
+
+    assert!(
+        missing.is_empty(),
+        "examples without answers in fixtures/answers.toml:\n{}",
+        missing.join("\n")
+    );
+}
@@ -0,0 +1,40 @@
+//! The solvers as a JavaScript module, built from this directory with
+//! `cargo build --release --target wasm32-unknown-unknown` and `wasm-bindgen`.
+
+use advent_of_code_2023::registry;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Answers `part` of `day` for `input`. In JavaScript an error is thrown with the message.
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, String> {
+    let Some(solver) = registry::get(day) else {
+        return Err(format!("day {day} is not solved yet"));
+    };
+    if !(1..=2).contains(&part) {
+        return Err(format!("part {part} is not 1 or 2"));
+    }
+
+    solver
+        .parse(input)
+        .and_then(|parsed| solver.answer(parsed.as_ref(), part))
+        .map_err(|e| format!("day {day} part {part}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_works() {
+        assert_eq!(solve(15, 1, "rn=1\n"), Ok("30".to_string()));
+        assert_eq!(
+            solve(6, 2, "Time: 7 15 30\nDistance: 9 40 200"),
+            Ok("71503".to_string())
+        );
+        assert!(solve(25, 1, "").is_err());
+        assert!(solve(15, 3, "rn=1").is_err());
+        assert!(solve(6, 1, "Time: 7")
+            .unwrap_err()
+            .starts_with("day 6 part 1: "));
+    }
+}
@@ -0,0 +1,26 @@
+//! Runs the JavaScript binding of the solvers in a headless wasm runtime, with
+//! `cargo test --target wasm32-unknown-unknown` from this directory. Node is used to run the
+//! tests, see `.cargo/config.toml` for the runner.
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use advent_of_code_2023_wasm::solve;
+
+#[wasm_bindgen_test]
+fn solve_answers_the_examples() {
+    let day6 = include_str!("../../fixtures/day6/example.txt");
+    let day15 = include_str!("../../fixtures/day15/example.txt");
+
+    assert_eq!(solve(6, 1, day6).unwrap(), "288");
+    assert_eq!(solve(6, 2, day6).unwrap(), "71503");
+    assert_eq!(solve(15, 1, day15).unwrap(), "1320");
+    assert_eq!(solve(15, 2, day15).unwrap(), "145");
+}
+
+#[wasm_bindgen_test]
+fn solve_reports_errors() {
+    assert!(solve(25, 1, "").is_err());
+    assert!(solve(15, 3, "rn=1").is_err());
+    assert!(solve(7, 1, "32T3K x").is_err());
+}